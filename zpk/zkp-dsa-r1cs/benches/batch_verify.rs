@@ -0,0 +1,31 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use zkp_dsa_r1cs::{prove_proof, setup_keys, verify_batch, Bls12DSACircuit, DSAProver, Groth16Prover};
+
+const NUM_PROOFS: usize = 100;
+
+fn bench_batch_vs_sequential(c: &mut Criterion) {
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let (pk, vk) = setup_keys(&circuit, Some(0)).expect("Setup failed");
+    let items: Vec<_> = (0..NUM_PROOFS as u64)
+        .map(|seed| {
+            let proof = prove_proof(&pk, &circuit, Some(seed)).expect("Proving failed");
+            (circuit.public_inputs(), proof)
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify_100_proofs");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            items
+                .iter()
+                .map(|(inputs, proof)| Groth16Prover::verify(&vk, inputs, proof).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("batch", |b| b.iter(|| verify_batch(&vk, &items)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_vs_sequential);
+criterion_main!(benches);
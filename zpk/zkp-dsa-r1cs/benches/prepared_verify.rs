@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use zkp_dsa_r1cs::{
+    prepare_vk, prove_proof, setup_keys, verify_prepared, Bls12DSACircuit, DSAProver,
+    Groth16Prover,
+};
+
+fn bench_prepared_vs_unprepared(c: &mut Criterion) {
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let (pk, vk) = setup_keys(&circuit, Some(0)).expect("Setup failed");
+    let proof = prove_proof(&pk, &circuit, Some(0)).expect("Proving failed");
+    let inputs = circuit.public_inputs();
+    let pvk = prepare_vk(&vk);
+
+    let mut group = c.benchmark_group("verify_single_proof");
+    group.bench_function("unprepared", |b| {
+        b.iter(|| Groth16Prover::verify(&vk, &inputs, &proof).unwrap())
+    });
+    group.bench_function("prepared", |b| b.iter(|| verify_prepared(&pvk, &inputs, &proof).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_prepared_vs_unprepared);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use ark_bls12_381::Bls12_381;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use criterion::{criterion_group, criterion_main, Criterion};
+use zkp_dsa_r1cs::Bls12DSACircuit;
+
+// The default parameters used by the binary
+fn default_circuit() -> Bls12DSACircuit {
+    Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3)
+}
+
+fn bench_setup(c: &mut Criterion) {
+    let circuit = default_circuit();
+    let mut rng = StdRng::seed_from_u64(0u64);
+    c.bench_function("groth16_setup", |b| {
+        b.iter(|| Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap())
+    });
+}
+
+fn bench_prove(c: &mut Criterion) {
+    let circuit = default_circuit();
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let (pk, _) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+    c.bench_function("groth16_prove", |b| {
+        b.iter(|| Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).unwrap())
+    });
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let circuit = default_circuit();
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+    let public_inputs = circuit.public_inputs();
+    c.bench_function("groth16_verify", |b| {
+        b.iter(|| Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_setup, bench_prove, bench_verify);
+criterion_main!(benches);
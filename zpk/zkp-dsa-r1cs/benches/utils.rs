@@ -0,0 +1,41 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zkp_dsa_r1cs::utils::{modular_exponentiation_windowed, MontgomeryCtx};
+
+// Largest prime below 2^64
+const MODULUS: u64 = 18_446_744_073_709_551_557;
+
+fn square_and_multiply(base: u64, exp: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let mut result = 1u128;
+    let mut base = base as u128 % modulus;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = (result * base) % modulus;
+        }
+        base = (base * base) % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+fn bench_modular_exponentiation(c: &mut Criterion) {
+    let base = 0x1234_5678_9abc_def0;
+    let exp = 0xfedc_ba98_7654_3210;
+    let mut group = c.benchmark_group("modular_exponentiation");
+    // `%`-based reduction on u128 products
+    group.bench_function("square_and_multiply", |b| {
+        b.iter(|| square_and_multiply(black_box(base), black_box(exp), black_box(MODULUS)))
+    });
+    group.bench_function("windowed_4bit", |b| {
+        b.iter(|| modular_exponentiation_windowed(black_box(base), black_box(exp), black_box(MODULUS)))
+    });
+    let ctx = MontgomeryCtx::new(MODULUS);
+    group.bench_function("montgomery", |b| {
+        b.iter(|| ctx.pow(black_box(base), black_box(exp)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_modular_exponentiation);
+criterion_main!(benches);
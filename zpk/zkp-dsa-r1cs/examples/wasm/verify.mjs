@@ -0,0 +1,23 @@
+// Verifies a proof in the browser with the output of
+// `wasm-pack build --target web -- --features wasm`. The key and proof are
+// the `vk.bin` and proof files written by the CLI, served next to this page.
+import init, { verify_proof } from "../../pkg/zkp_dsa_r1cs.js";
+
+async function fetchBytes(url) {
+  const response = await fetch(url);
+  return new Uint8Array(await response.arrayBuffer());
+}
+
+await init();
+
+const vk = await fetchBytes("keys/vk.bin");
+const proof = await fetchBytes("proof.bin");
+const publicInputs = JSON.stringify({
+  y: "2", h_x: "2", r: "2", s: "1", p: "7", q: "3", g: "2",
+});
+
+try {
+  console.log("Proof verification result:", verify_proof(vk, publicInputs, proof));
+} catch (e) {
+  console.error("Malformed input:", e.message);
+}
@@ -0,0 +1,29 @@
+#ifndef PQ_ZKP_H
+#define PQ_ZKP_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Verifies a Groth16 proof of DSA signature verification.
+ *
+ * vk:     verifying key as written to vk.bin by `zkp-dsa-r1cs setup`
+ * inputs: UTF-8 JSON with decimal-string fields y, h_x, r, s, p, q, g
+ * proof:  proof file written by `zkp-dsa-r1cs prove`
+ *
+ * Returns 1 if the proof is valid, 0 if it is invalid and -1 if a pointer
+ * is null or any input is malformed.
+ */
+int pq_zkp_verify(const uint8_t *vk_ptr, size_t vk_len,
+                  const uint8_t *inputs_ptr, size_t inputs_len,
+                  const uint8_t *proof_ptr, size_t proof_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PQ_ZKP_H */
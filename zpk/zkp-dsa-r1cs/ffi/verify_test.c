@@ -0,0 +1,72 @@
+/*
+ * Smoke test for the C API. From the crate root, generate keys and a proof
+ * first, then build the library with the C API as a shared library (`cargo
+ * run` rebuilds it without the feature):
+ *
+ *   cargo run -- --seed 1 setup --params src/tests/fixtures/dsa_inputs.json --out-dir /tmp/pq-zkp
+ *   cargo run -- --seed 1 prove --pk /tmp/pq-zkp/pk.bin \
+ *       --params src/tests/fixtures/dsa_inputs.json --out /tmp/pq-zkp/proof.bin
+ *   cargo rustc --lib --features cffi --crate-type cdylib
+ *   cc -Iffi ffi/verify_test.c -Ltarget/debug -lzkp_dsa_r1cs -o /tmp/pq-zkp/verify_test
+ *   LD_LIBRARY_PATH=target/debug /tmp/pq-zkp/verify_test \
+ *       /tmp/pq-zkp/vk.bin src/tests/fixtures/dsa_inputs.json /tmp/pq-zkp/proof.bin
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "pq_zkp.h"
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *f = fopen(path, "rb");
+    if (!f) {
+        perror(path);
+        exit(2);
+    }
+    fseek(f, 0, SEEK_END);
+    *len = (size_t)ftell(f);
+    rewind(f);
+    uint8_t *buf = malloc(*len);
+    if (!buf || fread(buf, 1, *len, f) != *len) {
+        fprintf(stderr, "failed to read %s\n", path);
+        exit(2);
+    }
+    fclose(f);
+    return buf;
+}
+
+static int failures = 0;
+
+static void expect(const char *name, int actual, int expected) {
+    if (actual != expected) {
+        fprintf(stderr, "FAIL %s: got %d, expected %d\n", name, actual, expected);
+        failures++;
+    } else {
+        printf("ok   %s\n", name);
+    }
+}
+
+int main(int argc, char **argv) {
+    if (argc != 4) {
+        fprintf(stderr, "usage: %s VK INPUTS_JSON PROOF\n", argv[0]);
+        return 2;
+    }
+    size_t vk_len, inputs_len, proof_len;
+    uint8_t *vk = read_file(argv[1], &vk_len);
+    uint8_t *inputs = read_file(argv[2], &inputs_len);
+    uint8_t *proof = read_file(argv[3], &proof_len);
+
+    expect("valid proof", pq_zkp_verify(vk, vk_len, inputs, inputs_len, proof, proof_len), 1);
+
+    const char *other = "{\"y\":\"2\",\"h_x\":\"1\",\"r\":\"2\",\"s\":\"1\",\"p\":\"7\",\"q\":\"3\",\"g\":\"2\"}";
+    expect("other inputs",
+           pq_zkp_verify(vk, vk_len, (const uint8_t *)other, strlen(other), proof, proof_len), 0);
+
+    expect("truncated proof", pq_zkp_verify(vk, vk_len, inputs, inputs_len, proof, proof_len / 2), -1);
+    expect("null key", pq_zkp_verify(NULL, 0, inputs, inputs_len, proof, proof_len), -1);
+
+    free(vk);
+    free(inputs);
+    free(proof);
+    return failures ? 1 : 0;
+}
@@ -0,0 +1,15 @@
+// Arbitrary bytes into the DER signature parser and the circuit constructor
+// built on it. Every input must either decode or return a `DerError`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkp_dsa_r1cs::{parse_der_signature, Bls12DSACircuit, DSAParams};
+
+const PARAMS: DSAParams = DSAParams { p: 2039, q: 1019, g: 4 };
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_der_signature(data);
+    if let Ok(circuit) = Bls12DSACircuit::from_der_signature(data, &PARAMS, 572, 789) {
+        let _ = circuit.validate_params();
+    }
+});
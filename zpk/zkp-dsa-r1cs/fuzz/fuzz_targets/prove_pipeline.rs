@@ -0,0 +1,62 @@
+// Whole circuits through `validate_params` and, when that accepts them, the
+// full setup / prove / verify pipeline. Nothing may panic, and a proof must
+// verify exactly when the signature verifies natively.
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use zkp_dsa_r1cs::utils::dsa_verify_native;
+use zkp_dsa_r1cs::{
+    check_satisfied, gen_test_params, prove_and_verify, seeded_rng, sign, Bls12DSACircuit,
+    ProverConfig, MIN_TEST_PARAM_BITS,
+};
+
+// The library's `Arbitrary` draws raw tuples, which almost never form a
+// valid domain, so half of the inputs are instead built from generated
+// parameters and a genuine signature, optionally tampered with, to reach
+// the prover at all.
+#[derive(Debug)]
+struct FuzzCircuit(Bls12DSACircuit);
+
+impl<'a> Arbitrary<'a> for FuzzCircuit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        if u.arbitrary::<bool>()? {
+            return Ok(FuzzCircuit(u.arbitrary()?));
+        }
+        // Small domains keep each setup and proof quick; narrower ones than
+        // `MIN_TEST_PARAM_BITS` have no nonce that signs
+        let bits = u.int_in_range(MIN_TEST_PARAM_BITS..=16)?;
+        let mut rng = seeded_rng(Some(u.arbitrary()?));
+        let (params, keys) = gen_test_params(bits, &mut rng);
+        let msg: &[u8] = u.arbitrary()?;
+        let sig = sign(msg, &keys, &params, &mut rng)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        let mut circuit = Bls12DSACircuit::from_signature(keys.y, msg, &sig, &params);
+        let (dr, ds): (u8, u8) = u.arbitrary()?;
+        circuit.sig.r += ark_bls12_381::Fr::from(dr as u64);
+        circuit.sig.s += ark_bls12_381::Fr::from(ds as u64);
+        Ok(FuzzCircuit(circuit))
+    }
+}
+
+fn to_u64(x: ark_bls12_381::Fr) -> u64 {
+    let x: num_bigint::BigUint = x.into();
+    u64::try_from(x).expect("Fuzz circuits are built from u64 values")
+}
+
+fuzz_target!(|input: FuzzCircuit| {
+    let circuit = input.0;
+    if circuit.validate_params().is_err() {
+        return;
+    }
+    let [y, h_x, r, s, p, q, g] = <[_; 7]>::try_from(circuit.public_inputs()).unwrap().map(to_u64);
+    if !dsa_verify_native(y, h_x, r, s, p, q, g) {
+        // The prover asserts satisfiability in debug builds, so invalid
+        // signatures stop at the constraint check
+        assert!(!check_satisfied(circuit).unwrap_or(false));
+        return;
+    }
+    let config = ProverConfig { seed: Some(0), ..ProverConfig::default() };
+    let valid = prove_and_verify(&circuit, &config).expect("Pipeline failed");
+    assert!(valid, "valid signature {} failed to verify", circuit);
+});
@@ -0,0 +1,13 @@
+// Arbitrary (y, h_x, r, s, p, q, g) tuples into `validate_params`, which
+// must return a `Result` for all of them instead of panicking
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkp_dsa_r1cs::Bls12DSACircuit;
+
+fuzz_target!(|values: [u64; 7]| {
+    let [y, h_x, r, s, p, q, g] = values;
+    let circuit = Bls12DSACircuit::new(y, h_x, r, s, p, q, g);
+    let _ = circuit.validate_params();
+    let _ = circuit.signature_inverse();
+});
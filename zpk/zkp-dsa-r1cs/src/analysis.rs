@@ -0,0 +1,181 @@
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, SynthesisError, SynthesisMode,
+};
+#[cfg(feature = "debug-constraints")]
+use ark_relations::r1cs::ConstraintSystemRef;
+use crate::circuit::DSAVerificationCircuit;
+
+// Size of the synthesized constraint system. `num_input_vars` counts the
+// public inputs only, not the constant `one` arkworks also allocates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintStats {
+    pub num_constraints: usize,
+    pub num_witness_vars: usize,
+    pub num_input_vars: usize,
+}
+
+// Synthesizes the circuit in setup mode (no witness assignment is kept) and
+// reports its size
+pub fn constraint_stats<F: PrimeField>(
+    circuit: &DSAVerificationCircuit<F>,
+) -> Result<ConstraintStats, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.clone().generate_constraints(cs.clone())?;
+    Ok(ConstraintStats {
+        num_constraints: cs.num_constraints(),
+        num_witness_vars: cs.num_witness_variables(),
+        num_input_vars: cs.num_instance_variables() - 1,
+    })
+}
+
+// A pre-flight for `Groth16Prover::setup` that skips sampling the SRS: the
+// circuit synthesizes in setup mode, every witness variable appears in at
+// least one constraint (an unconstrained one could take any value in a
+// proof), and the QAP domain over constraints + inputs, sized as Groth16
+// sizes it, exists in `F`. Parameter relations are checked separately by
+// `validate_params`, and a concrete witness by `check_satisfied`.
+pub fn dry_run_setup<F: PrimeField>(
+    circuit: &DSAVerificationCircuit<F>,
+) -> Result<ConstraintStats, SynthesisError> {
+    let matrices = constraint_matrices(circuit)?;
+    let mut used = vec![false; matrices.num_instance_variables + matrices.num_witness_variables];
+    for &(_, column) in matrices.a.iter().chain(&matrices.b).chain(&matrices.c).flatten() {
+        used[column] = true;
+    }
+    if !used[matrices.num_instance_variables..].iter().all(|&u| u) {
+        return Err(SynthesisError::UnconstrainedVariable);
+    }
+    GeneralEvaluationDomain::<F>::new(matrices.num_constraints + matrices.num_instance_variables)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    Ok(ConstraintStats {
+        num_constraints: matrices.num_constraints,
+        num_witness_vars: matrices.num_witness_variables,
+        num_input_vars: matrices.num_instance_variables - 1,
+    })
+}
+
+// Synthesizes the circuit with its witness and checks the R1CS directly,
+// which is far cheaper than a Groth16 setup and proof
+pub fn check_satisfied<F: PrimeField>(circuit: DSAVerificationCircuit<F>) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    cs.is_satisfied()
+}
+
+// The A, B and C matrices of the synthesized system, for external R1CS
+// tooling. Rows are constraints and each row lists its non-zero
+// `(coefficient, column)` entries. Columns index the full assignment: the
+// constant `one` at 0, then the public inputs, then the witnesses.
+pub fn constraint_matrices<F: PrimeField>(
+    circuit: &DSAVerificationCircuit<F>,
+) -> Result<ConstraintMatrices<F>, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.clone().generate_constraints(cs.clone())?;
+    // Inlines the symbolic linear combinations so rows only reference
+    // assignment columns
+    cs.finalize();
+    cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)
+}
+
+// Non-zero entries of A, B and C. The prover's MSMs over the A, B and C
+// queries scale with these rather than with the raw constraint count, so
+// they are the better measure of what a new gadget actually costs.
+pub fn r1cs_nnz<F: PrimeField>(
+    circuit: &DSAVerificationCircuit<F>,
+) -> Result<(usize, usize, usize), SynthesisError> {
+    let matrices = constraint_matrices(circuit)?;
+    Ok((matrices.a_num_non_zero, matrices.b_num_non_zero, matrices.c_num_non_zero))
+}
+
+// Rough peak memory of a BLS12-381 Groth16 setup and proof, in bytes. With
+// `m` assignment columns (the constant `one`, the public inputs and the
+// witnesses), `n` constraints, `z` non-zero matrix entries and the QAP domain
+// `d = (n + public inputs + 1).next_power_of_two()`:
+//
+//   assignment_bytes    = m * |Fr|
+//   matrix_bytes        = z * (|Fr| + |usize|)
+//   qap_bytes           = 3 * d * |Fr|              (A, B and C evaluations)
+//   proving_key_bytes   = m * (2 * |G1| + |G2|)     (A, B1 and B2 queries)
+//                       + (d - 1) * |G1|            (H query)
+//                       + witnesses * |G1|          (L query)
+//
+// using the in-memory sizes of the arkworks types. Everything is counted as
+// live at once, so `total_bytes` is an upper-end figure rather than a
+// measurement; allocator overhead and FFT scratch space are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub assignment_bytes: usize,
+    pub matrix_bytes: usize,
+    pub qap_bytes: usize,
+    pub proving_key_bytes: usize,
+}
+
+impl MemoryEstimate {
+    pub fn total_bytes(&self) -> usize {
+        self.assignment_bytes + self.matrix_bytes + self.qap_bytes + self.proving_key_bytes
+    }
+}
+
+// Estimates proving memory for any circuit over the BLS12-381 scalar field,
+// including `BatchDSACircuit`. Only setup-mode synthesis runs, so no witness
+// is computed and no key material is generated.
+pub fn estimate_memory<C: ConstraintSynthesizer<Fr>>(circuit: C) -> Result<MemoryEstimate, SynthesisError> {
+    use core::mem::size_of;
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+
+    let fr = size_of::<Fr>();
+    let g1 = size_of::<G1Affine>();
+    let g2 = size_of::<G2Affine>();
+    let columns = matrices.num_instance_variables + matrices.num_witness_variables;
+    let non_zero = matrices.a_num_non_zero + matrices.b_num_non_zero + matrices.c_num_non_zero;
+    let domain = (matrices.num_constraints + matrices.num_instance_variables).next_power_of_two();
+    Ok(MemoryEstimate {
+        assignment_bytes: columns * fr,
+        matrix_bytes: non_zero * (fr + size_of::<usize>()),
+        qap_bytes: 3 * domain * fr,
+        proving_key_bytes: columns * (2 * g1 + g2)
+            + (domain - 1) * g1
+            + matrices.num_witness_variables * g1,
+    })
+}
+
+// Logs the first unsatisfied constraint once a signature is synthesized,
+// together with the verification step it belongs to. `steps` holds the
+// index of each step's first constraint in ascending order. Setup mode has
+// no witness to check, so nothing is reported there. This goes to `log`
+// directly rather than through `debug_log!`, so `silent` doesn't drop a
+// report the build asked for.
+#[cfg(feature = "debug-constraints")]
+pub(crate) fn log_first_unsatisfied<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    steps: &[(usize, &'static str)],
+) {
+    if cs.is_in_setup_mode() {
+        return;
+    }
+    match cs.which_is_unsatisfied() {
+        // Without a tracing `ConstraintLayer` the trace is just the index
+        Ok(Some(trace)) => match trace.parse::<usize>() {
+            Ok(index) => {
+                let step = steps
+                    .iter()
+                    .rev()
+                    .find(|(start, _)| *start <= index)
+                    .map_or("unknown", |(_, name)| name);
+                log::debug!("unsatisfied constraint {} in step `{}`", index, step);
+            }
+            Err(_) => log::debug!("unsatisfied constraint at {}", trace),
+        },
+        Ok(None) => log::debug!("all {} constraints are satisfied", cs.num_constraints()),
+        Err(e) => log::debug!("could not check constraint satisfaction: {}", e),
+    }
+}
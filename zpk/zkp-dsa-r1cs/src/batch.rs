@@ -0,0 +1,94 @@
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use crate::circuit::{enforce_signature, DomainInputs, Input, SignatureInputs};
+use crate::dsa::DSAParams;
+
+// One entry of a batch: the signer's public key, the message hash and the
+// signature over it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DSASig {
+    pub y: u64,
+    pub h_x: u64,
+    pub r: u64,
+    pub s: u64,
+}
+
+// Proves in a single system that every signature verifies under one set of
+// domain parameters, with p, q and g allocated once and shared. The circuit
+// shape grows with the number of signatures, so keys from one batch size
+// can't be reused for another.
+#[derive(Clone, Debug)]
+pub struct BatchDSACircuit {
+    pub signatures: Vec<DSASig>,
+    pub params: DSAParams,
+    // One y for the whole batch; see `with_shared_y`
+    pub shared_y: bool,
+}
+
+impl BatchDSACircuit {
+    pub fn new(params: DSAParams, signatures: Vec<DSASig>) -> Self {
+        BatchDSACircuit { signatures, params, shared_y: false }
+    }
+
+    // For many messages signed by one key: y is allocated once, from the
+    // first signature, and every signature is checked against it. The y of
+    // the other entries is ignored, so one signed under a different key
+    // leaves the system unsatisfied. Changes the public inputs, and with
+    // them the keys, since y is no longer repeated per signature.
+    pub fn with_shared_y(mut self, shared: bool) -> Self {
+        self.shared_y = shared;
+        self
+    }
+
+    // The N the circuit was built for
+    pub fn num_signatures(&self) -> usize {
+        self.signatures.len()
+    }
+
+    // The y allocated once for the batch, if it is shared
+    fn shared_key(&self) -> Option<u64> {
+        self.signatures.first().filter(|_| self.shared_y).map(|sig| sig.y)
+    }
+
+    // p, q, g followed by y, h_x, r, s for each signature in order. With a
+    // shared y it comes once after g and each signature adds h_x, r, s.
+    pub fn public_inputs<F: PrimeField>(&self) -> Vec<F> {
+        let DSAParams { p, q, g } = self.params;
+        let mut inputs = vec![F::from(p), F::from(q), F::from(g)];
+        inputs.extend(self.shared_key().map(F::from));
+        for sig in &self.signatures {
+            if !self.shared_y {
+                inputs.push(F::from(sig.y));
+            }
+            inputs.extend([sig.h_x, sig.r, sig.s].map(F::from));
+        }
+        inputs
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for BatchDSACircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let DSAParams { p, q, g } = self.params;
+        let domain = DomainInputs {
+            p: Input::alloc(&cs, F::from(p))?.into(),
+            q: Input::alloc(&cs, F::from(q))?.into(),
+            g: Input::alloc(&cs, F::from(g))?,
+        };
+        let shared_y = self.shared_key().map(|y| Input::alloc(&cs, F::from(y))).transpose()?;
+        for sig in &self.signatures {
+            let y = match &shared_y {
+                Some(y) => Input { var: y.var, val: y.val.clone() },
+                None => Input::alloc(&cs, F::from(sig.y))?,
+            };
+            let inputs = SignatureInputs {
+                y,
+                h_x: Input::alloc(&cs, F::from(sig.h_x))?,
+                r: Input::alloc(&cs, F::from(sig.r))?,
+                s: Input::alloc(&cs, F::from(sig.s))?,
+            };
+            let witness = inputs.witness(&domain)?;
+            enforce_signature(&cs, &domain, &inputs, &witness)?;
+        }
+        Ok(())
+    }
+}
@@ -1,147 +1,739 @@
 use ark_bls12_381::Fr;
-use ark_ff::{One, PrimeField, Zero};
-use ark_relations::lc;
+use ark_ff::{FpParameters, One, PrimeField, Zero};
+use ark_relations::{lc, ns};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable};
-use crate::utils::{modular_inverse, modular_exponentiation};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::marker::PhantomData;
+use crate::dsa::{DSAParams, PublicKey, Signature};
+use crate::error::{CircuitError, ParamError};
+use crate::gadgets::{enforce_bits, multiply, pow_mod_with, reduce};
+use crate::utils::{
+    has_order, has_order_biguint, hash_to_scalar_biguint, is_probable_prime_biguint,
+    modular_inverse_biguint,
+};
 
-// DSA Verification Circuit for small parameters (p=7, q=3, g=3)
-#[derive(Clone)]
-pub struct DSAVerificationCircuit {
-    pub y: Fr,      // Public key
-    pub h_x: Fr,    // Message hash
-    pub r: Fr,      // Signature part r
-    pub s: Fr,      // Signature part s
-    pub p: Fr,      // Prime p
-    pub q: Fr,      // Prime q
-    pub g: Fr,      // Generator g
-}
-
-impl ConstraintSynthesizer<Fr> for DSAVerificationCircuit {
-    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
-        // Compute intermediate values from public inputs
-        let s_val = self.s.into_repr().as_ref()[0] as u64;
-        let q_val = self.q.into_repr().as_ref()[0] as u64;
-        let w_val = modular_inverse(s_val, q_val)?;
-        let h_x_val = self.h_x.into_repr().as_ref()[0] as u64;
-        let u1_val = (h_x_val * w_val) % q_val;
-        let r_val = self.r.into_repr().as_ref()[0] as u64;
-        let u2_val = (r_val * w_val) % q_val;
-        let g_val = self.g.into_repr().as_ref()[0] as u64;
-        let p_val = self.p.into_repr().as_ref()[0] as u64;
-        let g_u1_val = modular_exponentiation(g_val, u1_val, p_val);
-        let y_val = self.y.into_repr().as_ref()[0] as u64;
-        let y_u2_val = modular_exponentiation(y_val, u2_val, p_val);
-        let v_val = (g_u1_val * y_u2_val) % p_val;
-        let v_mod_q_val = v_val % q_val;
-        let r_mod_q_val = r_val % q_val;
-
-        // Debug prints to verify values
-        println!("w_val: {}, u1_val: {}, u2_val: {}", w_val, u1_val, u2_val);
-        println!("g_u1_val: {}, y_u2_val: {}, v_val: {}", g_u1_val, y_u2_val, v_val);
-        println!("v_mod_q_val: {}, r_mod_q_val: {}", v_mod_q_val, r_mod_q_val);
-
-        // Allocate public inputs (prefixed to suppress warnings)
-        let _y_var = cs.new_input_variable(|| Ok(self.y))?;
-        let _h_x_var = cs.new_input_variable(|| Ok(self.h_x))?;
-        let _r_var = cs.new_input_variable(|| Ok(self.r))?;
-        let _s_var = cs.new_input_variable(|| Ok(self.s))?;
-        let _p_var = cs.new_input_variable(|| Ok(self.p))?;
-        let _q_var = cs.new_input_variable(|| Ok(self.q))?;
-        let _g_var = cs.new_input_variable(|| Ok(self.g))?;
-
-        // Allocate witnesses
-        let w_var = cs.new_witness_variable(|| Ok(Fr::from(w_val)))?;
-        let u1_var = cs.new_witness_variable(|| Ok(Fr::from(u1_val)))?;
-        let u2_var = cs.new_witness_variable(|| Ok(Fr::from(u2_val)))?;
-        let g_u1_var = cs.new_witness_variable(|| Ok(Fr::from(g_u1_val)))?;
-        let y_u2_var = cs.new_witness_variable(|| Ok(Fr::from(y_u2_val)))?;
-        let v_var = cs.new_witness_variable(|| Ok(Fr::from(v_val)))?;
-        let v_mod_q_var = cs.new_witness_variable(|| Ok(Fr::from(v_mod_q_val)))?;
-        let r_mod_q_var = cs.new_witness_variable(|| Ok(Fr::from(r_mod_q_val)))?;
-
-        // Constants
-        let one = Fr::one();
-        let zero = Fr::zero();
-
-        // Constraint: w * s = 1 mod q
-        let ws_var = cs.new_witness_variable(|| Ok(Fr::from(w_val * s_val)))?;
-        let ws_remainder_var = cs.new_witness_variable(|| Ok(Fr::from((w_val * s_val) % q_val)))?;
-        let ws_quotient_var = cs.new_witness_variable(|| Ok(Fr::from((w_val * s_val) / q_val)))?;
-        let q_times_ws_quotient_var = cs.new_witness_variable(|| Ok(Fr::from(q_val * ((w_val * s_val) / q_val))))?;
-        cs.enforce_constraint(lc!() + w_var, lc!() + _s_var, lc!() + ws_var)?;
-        cs.enforce_constraint(lc!() + _q_var, lc!() + ws_quotient_var, lc!() + q_times_ws_quotient_var)?;
-        cs.enforce_constraint(
-            lc!() + ws_var - q_times_ws_quotient_var,
-            lc!() + (one, Variable::One),
-            lc!() + ws_remainder_var,
+// Miller-Rabin rounds for the primality check on q, which is exact for a
+// u64 q
+const PRIMALITY_ROUNDS: usize = 12;
+
+// Reduces an integer into the scalar field
+pub(crate) fn to_field<F: PrimeField>(value: &BigUint) -> F {
+    F::from(value.clone())
+}
+
+// `to_field` reduces silently, so values from the outside go through this
+// first to reject anything the field cannot represent
+pub(crate) fn check_fits<F: PrimeField>(name: &'static str, value: &BigUint) -> Result<(), ParamError> {
+    let modulus: BigUint = F::Params::MODULUS.into();
+    if *value >= modulus {
+        return Err(ParamError::FieldOverflow(name));
+    }
+    Ok(())
+}
+
+// Leftmost bitlen(q) bits of SHA-256(msg), reduced mod q
+fn message_hash(msg: &[u8], q: &BigUint) -> BigUint {
+    hash_to_scalar_biguint(&Sha256::digest(msg), q)
+}
+
+// DSA Verification Circuit over parameters of arbitrary size below the field
+// modulus. Generic over the scalar field so it can be proven on any pairing
+// curve; defaults to the BLS12-381 scalar field. The domain, the signer's key
+// and the signature are the `dsa` types over that field, so signatures under
+// one domain or key can share them.
+#[derive(Clone, Debug, Eq)]
+pub struct DSAVerificationCircuit<F: PrimeField = Fr> {
+    pub params: DSAParams<F>,
+    pub key: PublicKey<F>,
+    pub h_x: F, // Message hash
+    pub sig: Signature<F>,
+    // Recheck the signature natively before synthesis; see `with_strict_checks`
+    strict_checks: bool,
+}
+
+// Strict mode only decides whether a bad signature fails early, so it is not
+// part of the statement and two circuits differing only in it are equal
+impl<F: PrimeField> PartialEq for DSAVerificationCircuit<F> {
+    fn eq(&self, other: &Self) -> bool {
+        let parts = |c: &Self| (c.params, c.key, c.h_x, c.sig);
+        parts(self) == parts(other)
+    }
+}
+
+// The concrete BLS12-381 circuit used before the circuit became field-generic
+pub type Bls12DSACircuit = DSAVerificationCircuit<Fr>;
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    // The flat form, with the inputs in public input order
+    pub fn new(y: u64, h_x: u64, r: u64, s: u64, p: u64, q: u64, g: u64) -> Self {
+        Self::from_parts(&DSAParams { p, q, g }, PublicKey(y), h_x, &Signature { r, s })
+    }
+
+    // `from_parts` for values already in the field, e.g. wider than a u64
+    pub fn from_field_parts(
+        params: DSAParams<F>,
+        key: PublicKey<F>,
+        h_x: F,
+        sig: Signature<F>,
+    ) -> Self {
+        DSAVerificationCircuit { params, key, h_x, sig, strict_checks: false }
+    }
+
+    // `new`, but failing with `FieldOverflow` instead of reducing a value
+    // that is not below the field modulus. Every u64 fits in the BLS12-381
+    // and BN254 scalar fields; this matters for fields of 64 bits or less.
+    pub fn try_new(y: u64, h_x: u64, r: u64, s: u64, p: u64, q: u64, g: u64) -> Result<Self, ParamError> {
+        let values = [("y", y), ("h_x", h_x), ("r", r), ("s", s), ("p", p), ("q", q), ("g", g)];
+        for (name, value) in values {
+            check_fits::<F>(name, &BigUint::from(value))?;
+        }
+        Ok(Self::new(y, h_x, r, s, p, q, g))
+    }
+
+    // In strict mode `compute_witness` fails with `SignatureMismatch` when
+    // (g^u1 * y^u2 mod p) mod q != r mod q, and `generate_constraints` runs
+    // it before allocating anything, returning `Unsatisfiable` instead of
+    // emitting a system that only fails later inside the prover. Meant for
+    // debugging test vectors; leave it off when proving caller-supplied
+    // inputs, which the prover rejects anyway.
+    pub fn with_strict_checks(mut self, strict: bool) -> Self {
+        self.strict_checks = strict;
+        self
+    }
+
+    // Builds the circuit from a raw message instead of a precomputed hash.
+    // h_x follows FIPS 186-4: take SHA-256(msg), keep its leftmost bitlen(q)
+    // bits as a big-endian integer, and reduce that modulo q. There is no
+    // reduction mod 0, so q = 0 is rejected as `QNotPrime` up front; the
+    // other parameter checks are left to `validate_params`.
+    pub fn from_message(
+        msg: &[u8],
+        y: u64,
+        r: u64,
+        s: u64,
+        p: u64,
+        q: u64,
+        g: u64,
+    ) -> Result<Self, ParamError> {
+        if q == 0 {
+            return Err(ParamError::QNotPrime);
+        }
+        let mut circuit = Self::new(y, 0, r, s, p, q, g);
+        circuit.h_x = to_field(&message_hash(msg, &BigUint::from(q)));
+        Ok(circuit)
+    }
+
+    // Public inputs in the order they are allocated in `generate_constraints`
+    pub fn public_inputs(&self) -> Vec<F> {
+        let (DSAParams { p, q, g }, Signature { r, s }) = (self.params, self.sig);
+        vec![self.key.0, self.h_x, r, s, p, q, g]
+    }
+
+    // w = s^-1 mod q, the first step of DSA verification
+    pub fn signature_inverse(&self) -> Result<BigUint, CircuitError> {
+        modular_inverse_biguint(&self.sig.s.into(), &self.params.q.into())
+            .map_err(|_| CircuitError::NonInvertibleSignature)
+    }
+
+    // The witness values synthesis derives from these inputs, without
+    // running synthesis or the prover. In strict mode a witness showing the
+    // signature does not verify is an error.
+    pub fn compute_witness(&self) -> Result<DSAWitness, CircuitError> {
+        let witness = DSAWitness::compute(
+            &self.key.0.into(),
+            &self.h_x.into(),
+            &self.sig.r.into(),
+            &self.sig.s.into(),
+            &self.params.p.into(),
+            &self.params.q.into(),
+            &self.params.g.into(),
         )?;
+        if self.strict_checks && witness.v_mod_q != witness.r_mod_q {
+            return Err(CircuitError::SignatureMismatch {
+                v_mod_q: witness.v_mod_q,
+                r_mod_q: witness.r_mod_q,
+            });
+        }
+        Ok(witness)
+    }
+
+    // Runs the strict check ahead of synthesis; a no-op outside strict mode
+    pub(crate) fn check_strict(&self) -> Result<(), CircuitError> {
+        if self.strict_checks {
+            self.compute_witness()?;
+        }
+        Ok(())
+    }
+
+    // Checks the DSA domain relationships: q | p - 1, q is prime, g has
+    // order q mod p, and 0 < r, s < q, and that p is narrow enough for the
+    // circuit's arithmetic mod p not to wrap in the field
+    pub fn validate_params(&self) -> Result<(), ParamError> {
+        let p: BigUint = self.params.p.into();
+        let q: BigUint = self.params.q.into();
+        let g: BigUint = self.params.g.into();
+        let r: BigUint = self.sig.r.into();
+        let s: BigUint = self.sig.s.into();
+        let one = BigUint::one();
+
+        if p <= one || q <= one || (&p - 1u32) % &q != BigUint::zero() {
+            return Err(ParamError::QDoesNotDividePMinus1);
+        }
+        // Products of two values mod p are formed in the field before they
+        // are reduced, so they must not wrap around its characteristic
+        if 2 * p.bits() + 1 >= u64::from(F::Params::MODULUS_BITS) {
+            return Err(ParamError::ModulusTooWide);
+        }
+        // A composite q leaves s without an inverse for some signatures and
+        // breaks the order argument behind `has_order`
+        if !is_probable_prime_biguint(&q, PRIMALITY_ROUNDS) {
+            return Err(ParamError::QNotPrime);
+        }
+        let g_has_order_q = match (u64::try_from(&g), u64::try_from(&q), u64::try_from(&p)) {
+            (Ok(g), Ok(q), Ok(p)) => has_order(g, q, p),
+            _ => has_order_biguint(&g, &q, &p),
+        };
+        if g.is_zero() || g >= p || !g_has_order_q {
+            return Err(ParamError::GeneratorWrongOrder);
+        }
+        if r.is_zero() {
+            return Err(ParamError::SignatureComponentZero("r"));
+        }
+        if s.is_zero() {
+            return Err(ParamError::SignatureComponentZero("s"));
+        }
+        if r >= q || s >= q {
+            return Err(ParamError::SignatureOutOfRange);
+        }
+        Ok(())
+    }
+}
+
+// Named setters for the seven circuit inputs, so call sites can't swap two
+// of `new`'s positional arguments. Started by `DSAVerificationCircuit::builder`.
+#[derive(Clone, Debug, Default)]
+pub struct DSAVerificationCircuitBuilder<F: PrimeField = Fr> {
+    y: Option<u64>,
+    h_x: Option<u64>,
+    r: Option<u64>,
+    s: Option<u64>,
+    p: Option<u64>,
+    q: Option<u64>,
+    g: Option<u64>,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    pub fn builder() -> DSAVerificationCircuitBuilder<F> {
+        DSAVerificationCircuitBuilder::default()
+    }
+}
+
+impl<F: PrimeField> DSAVerificationCircuitBuilder<F> {
+    pub fn y(mut self, y: u64) -> Self {
+        self.y = Some(y);
+        self
+    }
+
+    pub fn h_x(mut self, h_x: u64) -> Self {
+        self.h_x = Some(h_x);
+        self
+    }
+
+    pub fn r(mut self, r: u64) -> Self {
+        self.r = Some(r);
+        self
+    }
+
+    pub fn s(mut self, s: u64) -> Self {
+        self.s = Some(s);
+        self
+    }
+
+    pub fn p(mut self, p: u64) -> Self {
+        self.p = Some(p);
+        self
+    }
+
+    pub fn q(mut self, q: u64) -> Self {
+        self.q = Some(q);
+        self
+    }
+
+    pub fn g(mut self, g: u64) -> Self {
+        self.g = Some(g);
+        self
+    }
+
+    // The same circuit as `new` with these values, or `MissingField` naming
+    // the first input, in `public_inputs` order, that was never set
+    pub fn build(self) -> Result<DSAVerificationCircuit<F>, CircuitError> {
+        let field = |value: Option<u64>, name| value.ok_or(CircuitError::MissingField(name));
+        Ok(DSAVerificationCircuit::new(
+            field(self.y, "y")?,
+            field(self.h_x, "h_x")?,
+            field(self.r, "r")?,
+            field(self.s, "s")?,
+            field(self.p, "p")?,
+            field(self.q, "q")?,
+            field(self.g, "g")?,
+        ))
+    }
+}
+
+// An allocated public input together with its integer value
+pub(crate) struct Input {
+    pub var: Variable,
+    pub val: BigUint,
+}
+
+impl Input {
+    pub fn alloc<F: PrimeField>(cs: &ConstraintSystemRef<F>, value: F) -> Result<Self, SynthesisError> {
+        Ok(Input { var: cs.new_input_variable(|| Ok(value))?, val: value.into() })
+    }
+
+    // A witness pinned to `value` by a linear constraint, so the constant
+    // ends up in the keys instead of the public inputs
+    pub fn constant<F: PrimeField>(cs: &ConstraintSystemRef<F>, value: F) -> Result<Self, SynthesisError> {
+        let var = cs.new_witness_variable(|| Ok(value))?;
+        cs.enforce_constraint(lc!() + var, lc!() + Variable::One, lc!() + (value, Variable::One))?;
+        Ok(Input { var, val: value.into() })
+    }
+}
+
+// A modulus of the domain: a public input, or with `var` set to `None` a
+// constant of the circuit that the reductions take as a scalar
+pub(crate) struct Modulus {
+    pub var: Option<Variable>,
+    pub val: BigUint,
+}
+
+impl Modulus {
+    pub fn constant<F: PrimeField>(value: F) -> Self {
+        Modulus { var: None, val: value.into() }
+    }
+}
+
+impl From<Input> for Modulus {
+    fn from(input: Input) -> Self {
+        Modulus { var: Some(input.var), val: input.val }
+    }
+}
+
+// Domain parameters shared by every signature checked in one system
+pub(crate) struct DomainInputs {
+    pub p: Modulus,
+    pub q: Modulus,
+    pub g: Input,
+}
+
+// Per-signature public inputs
+pub(crate) struct SignatureInputs {
+    pub y: Input,
+    pub h_x: Input,
+    pub r: Input,
+    pub s: Input,
+}
+
+// The intermediate values of DSA verification that the circuit allocates as
+// witnesses, computed natively. The signature is valid iff v_mod_q == r_mod_q.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DSAWitness {
+    pub w: BigUint,       // s^-1 mod q
+    pub u1: BigUint,      // h_x * w mod q
+    pub u2: BigUint,      // r * w mod q
+    pub g_u1: BigUint,    // g^u1 mod p
+    pub y_u2: BigUint,    // y^u2 mod p
+    pub v: BigUint,       // g_u1 * y_u2 mod p
+    pub v_mod_q: BigUint, // v mod q
+    pub r_mod_q: BigUint, // r mod q
+}
+
+impl DSAWitness {
+    // The pure native computation behind `generate_constraints`, kept apart
+    // from the R1CS so it can be tested on its own. Values are `BigUint`s so
+    // parameters spanning several limbs don't wrap around.
+    pub fn compute(
+        y: &BigUint,
+        h_x: &BigUint,
+        r: &BigUint,
+        s: &BigUint,
+        p: &BigUint,
+        q: &BigUint,
+        g: &BigUint,
+    ) -> Result<Self, CircuitError> {
+        // p is a public input nobody validated yet, and `modpow` panics on 0
+        if *p <= BigUint::one() {
+            return Err(CircuitError::ModulusTooSmall);
+        }
+        let w = modular_inverse_biguint(s, q).map_err(|_| CircuitError::NonInvertibleSignature)?;
+        let u1 = (h_x * &w) % q;
+        let u2 = (r * &w) % q;
+        let g_u1 = g.modpow(&u1, p);
+        let y_u2 = y.modpow(&u2, p);
+        let v = (&g_u1 * &y_u2) % p;
+        let v_mod_q = &v % q;
+        let r_mod_q = r % q;
+        Ok(DSAWitness { w, u1, u2, g_u1, y_u2, v, v_mod_q, r_mod_q })
+    }
+}
+
+impl SignatureInputs {
+    // The witness for this signature under `domain`
+    pub fn witness(&self, domain: &DomainInputs) -> Result<DSAWitness, CircuitError> {
+        DSAWitness::compute(
+            &self.y.val,
+            &self.h_x.val,
+            &self.r.val,
+            &self.s.val,
+            &domain.p.val,
+            &domain.q.val,
+            &domain.g.val,
+        )
+    }
+}
+
+// Emits the witnesses and constraints checking one DSA signature against
+// already-allocated inputs, so several signatures can share a domain.
+// `witness` must be `sig.witness(domain)`; this only lays down the R1CS.
+pub(crate) fn enforce_signature<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    domain: &DomainInputs,
+    sig: &SignatureInputs,
+    witness: &DSAWitness,
+) -> Result<(), SynthesisError> {
+    let (p_val, q_val) = (&domain.p.val, &domain.q.val);
+    let (h_x_val, r_val, s_val) = (&sig.h_x.val, &sig.r.val, &sig.s.val);
+    let (g_val, y_val) = (&domain.g.val, &sig.y.val);
+    let DSAWitness {
+        w: w_val,
+        u1: u1_val,
+        u2: u2_val,
+        g_u1: g_u1_val,
+        y_u2: y_u2_val,
+        v: v_val,
+        v_mod_q: v_mod_q_val,
+        r_mod_q: r_mod_q_val,
+    } = witness;
+
+    // Debug output to verify values
+    debug_log!("w_val: {}, u1_val: {}, u2_val: {}", w_val, u1_val, u2_val);
+    debug_log!("g_u1_val: {}, y_u2_val: {}, v_val: {}", g_u1_val, y_u2_val, v_val);
+    debug_log!("v_mod_q_val: {}, r_mod_q_val: {}", v_mod_q_val, r_mod_q_val);
+
+    let (p_var, q_var, g_var) = (domain.p.var, domain.q.var, domain.g.var);
+    let (y_var, h_x_var, r_var, s_var) = (sig.y.var, sig.h_x.var, sig.r.var, sig.s.var);
+
+    // Allocate witnesses
+    let w_var = cs.new_witness_variable(|| Ok(to_field(w_val)))?;
+    let u1_var = cs.new_witness_variable(|| Ok(to_field(u1_val)))?;
+    let u2_var = cs.new_witness_variable(|| Ok(to_field(u2_val)))?;
+    let v_var = cs.new_witness_variable(|| Ok(to_field(v_val)))?;
+    let v_mod_q_var = cs.new_witness_variable(|| Ok(to_field(v_mod_q_val)))?;
+    let r_mod_q_var = cs.new_witness_variable(|| Ok(to_field(r_mod_q_val)))?;
+
+    // Constants
+    let one = F::one();
+    let zero = F::zero();
+
+    // Index of the first constraint of each step, so `debug-constraints`
+    // builds can name the step a failing constraint belongs to
+    let mut steps = Vec::with_capacity(6);
+
+    // Each step runs in its own namespace, so with a tracing
+    // `ConstraintLayer` installed `which_is_unsatisfied` reports a path
+    // through e.g. `ws_reduction` instead of a bare index
+
+    // Constraint: w * s = 1 mod q. `mul_mod` needs its operands to fit in
+    // bitlen(q) bits, and w is the one operand not produced by a reduction,
+    // so it is decomposed first; that pins w to s^-1 mod q up to adding q.
+    steps.push((cs.num_constraints(), "w * s = 1 mod q"));
+    {
+        let ns = ns!(cs, "ws_reduction");
+        let cs = ns.cs();
+        enforce_bits(&cs, w_var, q_val.bits() as usize)?;
+        let ws_remainder_var = multiply(&cs, w_var, w_val, s_var, s_val, q_var, q_val)?;
         cs.enforce_constraint(
             lc!() + ws_remainder_var - (one, Variable::One),
             lc!() + (one, Variable::One),
             lc!() + (zero, Variable::One),
         )?;
+    }
 
-        // Constraint: u1 = h_x * w mod q
-        let u1_product_var = cs.new_witness_variable(|| Ok(Fr::from(h_x_val * w_val)))?;
-        let u1_remainder_var = cs.new_witness_variable(|| Ok(Fr::from((h_x_val * w_val) % q_val)))?;
-        let u1_quotient_var = cs.new_witness_variable(|| Ok(Fr::from((h_x_val * w_val) / q_val)))?;
-        let q_times_u1_quotient_var = cs.new_witness_variable(|| Ok(Fr::from(q_val * ((h_x_val * w_val) / q_val))))?;
-        cs.enforce_constraint(lc!() + _h_x_var, lc!() + w_var, lc!() + u1_product_var)?;
-        cs.enforce_constraint(lc!() + _q_var, lc!() + u1_quotient_var, lc!() + q_times_u1_quotient_var)?;
-        cs.enforce_constraint(
-            lc!() + u1_product_var - q_times_u1_quotient_var,
-            lc!() + (one, Variable::One),
-            lc!() + u1_remainder_var,
-        )?;
+    // Constraint: u1 = h_x * w mod q
+    steps.push((cs.num_constraints(), "u1 = h_x * w mod q"));
+    {
+        let ns = ns!(cs, "u1_reduction");
+        let cs = ns.cs();
+        let u1_remainder_var = multiply(&cs, h_x_var, h_x_val, w_var, w_val, q_var, q_val)?;
         cs.enforce_constraint(
             lc!() + u1_remainder_var - u1_var,
             lc!() + (one, Variable::One),
             lc!() + (zero, Variable::One),
         )?;
+    }
 
-        // Constraint: u2 = r * w mod q
-        let u2_product_var = cs.new_witness_variable(|| Ok(Fr::from(r_val * w_val)))?;
-        let u2_remainder_var = cs.new_witness_variable(|| Ok(Fr::from((r_val * w_val) % q_val)))?;
-        let u2_quotient_var = cs.new_witness_variable(|| Ok(Fr::from((r_val * w_val) / q_val)))?;
-        let q_times_u2_quotient_var = cs.new_witness_variable(|| Ok(Fr::from(q_val * ((r_val * w_val) / q_val))))?;
-        cs.enforce_constraint(lc!() + _r_var, lc!() + w_var, lc!() + u2_product_var)?;
-        cs.enforce_constraint(lc!() + _q_var, lc!() + u2_quotient_var, lc!() + q_times_u2_quotient_var)?;
-        cs.enforce_constraint(
-            lc!() + u2_product_var - q_times_u2_quotient_var,
-            lc!() + (one, Variable::One),
-            lc!() + u2_remainder_var,
-        )?;
+    // Constraint: u2 = r * w mod q
+    steps.push((cs.num_constraints(), "u2 = r * w mod q"));
+    {
+        let ns = ns!(cs, "u2_reduction");
+        let cs = ns.cs();
+        let u2_remainder_var = multiply(&cs, r_var, r_val, w_var, w_val, q_var, q_val)?;
         cs.enforce_constraint(
             lc!() + u2_remainder_var - u2_var,
             lc!() + (one, Variable::One),
             lc!() + (zero, Variable::One),
         )?;
+    }
 
-        // Constraint: v = g_u1 * y_u2 mod p
-        let v_product_var = cs.new_witness_variable(|| Ok(Fr::from(g_u1_val * y_u2_val)))?;
-        let v_remainder_var = cs.new_witness_variable(|| Ok(Fr::from((g_u1_val * y_u2_val) % p_val)))?;
-        let v_quotient_var = cs.new_witness_variable(|| Ok(Fr::from((g_u1_val * y_u2_val) / p_val)))?;
-        let p_times_v_quotient_var = cs.new_witness_variable(|| Ok(Fr::from(p_val * ((g_u1_val * y_u2_val) / p_val))))?;
-        cs.enforce_constraint(lc!() + g_u1_var, lc!() + y_u2_var, lc!() + v_product_var)?;
-        cs.enforce_constraint(lc!() + _p_var, lc!() + v_quotient_var, lc!() + p_times_v_quotient_var)?;
-        cs.enforce_constraint(
-            lc!() + v_product_var - p_times_v_quotient_var,
-            lc!() + (one, Variable::One),
-            lc!() + v_remainder_var,
-        )?;
+    // Constraint: g_u1 = g^u1 mod p and y_u2 = y^u2 mod p. `pow_mod`
+    // decomposes the exponent variable it is given, so the bits it
+    // consumes reconstruct exactly the u1_var / u2_var pinned above.
+    steps.push((cs.num_constraints(), "g^u1 mod p and y^u2 mod p"));
+    let exp_bits = q_val.bits() as usize;
+    let g_u1_var = {
+        let ns = ns!(cs, "g_pow_u1");
+        pow_mod_with(&ns.cs(), g_var, g_val, u1_var, u1_val, exp_bits, p_var, p_val)?
+    };
+    let y_u2_var = {
+        let ns = ns!(cs, "y_pow_u2");
+        pow_mod_with(&ns.cs(), y_var, y_val, u2_var, u2_val, exp_bits, p_var, p_val)?
+    };
+
+    // Constraint: v = g_u1 * y_u2 mod p
+    steps.push((cs.num_constraints(), "v = g_u1 * y_u2 mod p"));
+    {
+        let ns = ns!(cs, "v_reduction");
+        let cs = ns.cs();
+        let v_remainder_var =
+            multiply(&cs, g_u1_var, g_u1_val, y_u2_var, y_u2_val, p_var, p_val)?;
         cs.enforce_constraint(
             lc!() + v_remainder_var - v_var,
             lc!() + (one, Variable::One),
             lc!() + (zero, Variable::One),
         )?;
+    }
 
-        // Constraint: v_mod_q == r_mod_q
+    // Constraint: v_mod_q == r_mod_q, with both pinned to the reductions
+    // of v and r mod q so a prover cannot pick them freely
+    steps.push((cs.num_constraints(), "v mod q == r mod q"));
+    {
+        let ns = ns!(cs, "final_equality");
+        let cs = ns.cs();
+        // v < p, and r < q <= p for a valid signature
+        let dividend_bits = p_val.bits() as usize;
+        for (var, val, reduced_var) in [(v_var, v_val, v_mod_q_var), (r_var, r_val, r_mod_q_var)] {
+            let remainder_var = reduce(&cs, var, val, dividend_bits, q_var, q_val)?;
+            cs.enforce_constraint(
+                lc!() + remainder_var - reduced_var,
+                lc!() + (one, Variable::One),
+                lc!() + (zero, Variable::One),
+            )?;
+        }
         cs.enforce_constraint(
             lc!() + v_mod_q_var - r_mod_q_var,
             lc!() + (one, Variable::One),
             lc!() + (zero, Variable::One),
         )?;
+    }
 
-        Ok(())
+    #[cfg(feature = "debug-constraints")]
+    crate::analysis::log_first_unsatisfied(cs, &steps);
+    #[cfg(not(feature = "debug-constraints"))]
+    let _ = steps;
+
+    Ok(())
+}
+
+// Seven arbitrary u64 inputs in `new` order. Almost none of them form a
+// valid domain, so fuzz targets go through `validate_params` first.
+#[cfg(feature = "arbitrary")]
+impl<'a, F: PrimeField> arbitrary::Arbitrary<'a> for DSAVerificationCircuit<F> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let [y, h_x, r, s, p, q, g]: [u64; 7] = u.arbitrary()?;
+        Ok(Self::new(y, h_x, r, s, p, q, g))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u64; 7] as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+// Renders every parameter as a decimal integer, domain first, e.g.
+// `DSA(p=7, q=3, g=2, y=2, r=2, s=1, h=2)`
+impl<F: PrimeField> fmt::Display for DSAVerificationCircuit<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dec = |x: F| -> BigUint { x.into() };
+        write!(
+            f,
+            "DSA(p={}, q={}, g={}, y={}, r={}, s={}, h={})",
+            dec(self.params.p),
+            dec(self.params.q),
+            dec(self.params.g),
+            dec(self.key.0),
+            dec(self.sig.r),
+            dec(self.sig.s),
+            dec(self.h_x)
+        )
+    }
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    // Allocates the public inputs in `public_inputs` order
+    fn alloc_inputs(
+        &self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<(DomainInputs, SignatureInputs), SynthesisError> {
+        let y = Input::alloc(cs, self.key.0)?;
+        let h_x = Input::alloc(cs, self.h_x)?;
+        let r = Input::alloc(cs, self.sig.r)?;
+        let s = Input::alloc(cs, self.sig.s)?;
+        let p = Input::alloc(cs, self.params.p)?;
+        let q = Input::alloc(cs, self.params.q)?;
+        let g = Input::alloc(cs, self.params.g)?;
+        let (p, q) = (p.into(), q.into());
+        Ok((DomainInputs { p, q, g }, SignatureInputs { y, h_x, r, s }))
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for DSAVerificationCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.check_strict()?;
+        let (domain, sig) = self.alloc_inputs(&cs)?;
+        let witness = sig.witness(&domain)?;
+        enforce_signature(&cs, &domain, &sig, &witness)
+    }
+}
+
+// A `DSAVerificationCircuit` with its p, q and g compiled into the circuit
+// as constants, leaving y, h_x, r and s as the only public inputs. Keys from
+// its setup only accept proofs for that one domain, and verification saves
+// three public-input terms. p and q enter the reductions as scalars, through
+// the `_const` gadgets, which makes the system smaller than the flat one;
+// g is a witness pinned by one linear constraint, as `pow_mod` reduces its
+// base as a variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedDomainCircuit<F: PrimeField = Fr> {
+    pub circuit: DSAVerificationCircuit<F>,
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    // The circuit for the domain (p, q, g) alone, ready for setup; fill in
+    // each signature to prove with `with_signature`. Until then it holds
+    // y = 1, h_x = 0 and r = s = 1, which verify in every domain, so the
+    // native witness computation that setup runs has something to invert.
+    pub fn with_fixed_domain(p: u64, q: u64, g: u64) -> FixedDomainCircuit<F> {
+        let params = DSAParams { p, q, g };
+        let circuit = Self::from_parts(&params, PublicKey(1), 0, &Signature { r: 1, s: 1 });
+        FixedDomainCircuit { circuit }
+    }
+}
+
+impl<F: PrimeField> FixedDomainCircuit<F> {
+    // The same domain with the signature on h_x under `key` as its inputs,
+    // so one set of keys from the domain's setup proves any of them
+    pub fn with_signature(mut self, key: PublicKey, h_x: u64, sig: &Signature) -> Self {
+        self.circuit.key = key.to_field();
+        self.circuit.h_x = F::from(h_x);
+        self.circuit.sig = sig.to_field();
+        self
+    }
+
+    // See `DSAVerificationCircuit::with_strict_checks`
+    pub fn with_strict_checks(mut self, strict: bool) -> Self {
+        self.circuit.strict_checks = strict;
+        self
+    }
+
+    // Public inputs in the order they are allocated in `generate_constraints`
+    pub fn public_inputs(&self) -> Vec<F> {
+        let c = &self.circuit;
+        vec![c.key.0, c.h_x, c.sig.r, c.sig.s]
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for FixedDomainCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let c = self.circuit;
+        c.check_strict()?;
+        let y = Input::alloc(&cs, c.key.0)?;
+        let h_x = Input::alloc(&cs, c.h_x)?;
+        let r = Input::alloc(&cs, c.sig.r)?;
+        let s = Input::alloc(&cs, c.sig.s)?;
+        let p = Modulus::constant(c.params.p);
+        let q = Modulus::constant(c.params.q);
+        let g = Input::constant(&cs, c.params.g)?;
+
+        let domain = DomainInputs { p, q, g };
+        let sig = SignatureInputs { y, h_x, r, s };
+        let witness = sig.witness(&domain)?;
+        enforce_signature(&cs, &domain, &sig, &witness)
+    }
+}
+
+// Replacement values for intermediates of `DSAWitness`, for soundness tests
+// that check each constraint actually rejects a forged value. Unset fields
+// keep the honestly computed value.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Debug, Clone, Default)]
+pub struct WitnessOverrides {
+    pub w: Option<BigUint>,
+    pub u1: Option<BigUint>,
+    pub u2: Option<BigUint>,
+    pub g_u1: Option<BigUint>,
+    pub y_u2: Option<BigUint>,
+    pub v: Option<BigUint>,
+    pub v_mod_q: Option<BigUint>,
+    pub r_mod_q: Option<BigUint>,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl WitnessOverrides {
+    fn apply(&self, witness: &mut DSAWitness) {
+        let fields = [
+            (&mut witness.w, &self.w),
+            (&mut witness.u1, &self.u1),
+            (&mut witness.u2, &self.u2),
+            (&mut witness.g_u1, &self.g_u1),
+            (&mut witness.y_u2, &self.y_u2),
+            (&mut witness.v, &self.v),
+            (&mut witness.v_mod_q, &self.v_mod_q),
+            (&mut witness.r_mod_q, &self.r_mod_q),
+        ];
+        for (value, replacement) in fields {
+            if let Some(replacement) = replacement {
+                *value = replacement.clone();
+            }
+        }
+    }
+}
+
+// A `DSAVerificationCircuit` whose witness is patched by `WitnessOverrides`
+// between the native computation and synthesis. Only built for tests and
+// with the `test-utils` feature, so it never reaches production code.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Clone)]
+pub struct WitnessOverrideCircuit<F: PrimeField = Fr> {
+    pub circuit: DSAVerificationCircuit<F>,
+    pub overrides: WitnessOverrides,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    pub fn with_witness_override(self, overrides: WitnessOverrides) -> WitnessOverrideCircuit<F> {
+        WitnessOverrideCircuit { circuit: self, overrides }
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<F: PrimeField> ConstraintSynthesizer<F> for WitnessOverrideCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.circuit.check_strict()?;
+        let (domain, sig) = self.circuit.alloc_inputs(&cs)?;
+        let mut witness = sig.witness(&domain)?;
+        self.overrides.apply(&mut witness);
+        enforce_signature(&cs, &domain, &sig, &witness)
     }
 }
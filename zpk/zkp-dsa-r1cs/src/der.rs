@@ -0,0 +1,86 @@
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use crate::circuit::{check_fits, to_field, DSAVerificationCircuit};
+use crate::dsa::{DSAParams, Signature};
+use crate::error::DerError;
+
+const SEQUENCE_TAG: u8 = 0x30;
+const INTEGER_TAG: u8 = 0x02;
+
+// Splits one tag-length-value element off the front of `input`, accepting
+// only the definite, minimal length encodings DER allows
+fn read_element(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), DerError> {
+    let (&found, rest) = input.split_first().ok_or(DerError::Truncated)?;
+    if found != tag {
+        return Err(DerError::UnexpectedTag { expected: tag, found });
+    }
+    let (&first, mut rest) = rest.split_first().ok_or(DerError::Truncated)?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let num_bytes = (first & 0x7f) as usize;
+        // 0x80 is BER's indefinite form; more than 8 bytes can't be a usize
+        if num_bytes == 0 || num_bytes > 8 {
+            return Err(DerError::NonCanonicalLength);
+        }
+        if rest.len() < num_bytes {
+            return Err(DerError::Truncated);
+        }
+        let (len_bytes, tail) = rest.split_at(num_bytes);
+        rest = tail;
+        let len = len_bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        if len_bytes[0] == 0 || len < 0x80 {
+            return Err(DerError::NonCanonicalLength);
+        }
+        len
+    };
+    if rest.len() < len {
+        return Err(DerError::Truncated);
+    }
+    Ok(rest.split_at(len))
+}
+
+fn read_unsigned_integer(input: &[u8]) -> Result<(BigUint, &[u8]), DerError> {
+    let (bytes, rest) = read_element(input, INTEGER_TAG)?;
+    match bytes {
+        [] => Err(DerError::Truncated),
+        [b, ..] if b & 0x80 != 0 => Err(DerError::NegativeInteger),
+        // A leading zero is only allowed to clear the sign bit of the next byte
+        [0, b, ..] if b & 0x80 == 0 => Err(DerError::NonCanonicalInteger),
+        _ => Ok((BigUint::from_bytes_be(bytes), rest)),
+    }
+}
+
+// Decodes a DER `SEQUENCE { INTEGER r, INTEGER s }`, the signature format
+// produced by OpenSSL and most other DSA tooling
+pub fn parse_der_signature(der: &[u8]) -> Result<(BigUint, BigUint), DerError> {
+    let (body, rest) = read_element(der, SEQUENCE_TAG)?;
+    if !rest.is_empty() {
+        return Err(DerError::TrailingData);
+    }
+    let (r, body) = read_unsigned_integer(body)?;
+    let (s, body) = read_unsigned_integer(body)?;
+    if !body.is_empty() {
+        return Err(DerError::TrailingData);
+    }
+    Ok((r, s))
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    // Builds the circuit from a DER-encoded signature. r and s must fit in the
+    // scalar field, as `to_field` would otherwise reduce them silently; range
+    // checks against q are left to `validate_params`.
+    pub fn from_der_signature(
+        der: &[u8],
+        params: &DSAParams,
+        y: u64,
+        h_x: u64,
+    ) -> Result<Self, DerError> {
+        let (r, s) = parse_der_signature(der)?;
+        check_fits::<F>("r", &r)?;
+        check_fits::<F>("s", &s)?;
+        let mut circuit = Self::new(y, h_x, 0, 0, params.p, params.q, params.g);
+        circuit.sig = Signature { r: to_field(&r), s: to_field(&s) };
+        Ok(circuit)
+    }
+}
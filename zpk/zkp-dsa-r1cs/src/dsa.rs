@@ -0,0 +1,191 @@
+use ark_ff::PrimeField;
+use ark_std::rand::{Rng, RngCore};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use crate::circuit::DSAVerificationCircuit;
+use crate::error::ParamError;
+use crate::utils::{
+    dsa_sign_components, dsa_verify_native, find_generator, hash_to_scalar, is_prime,
+    modular_exponentiation,
+};
+
+// DSA domain parameters: primes p and q with q | p - 1, and g of order q.
+// Native signing works on u64s; `DSAVerificationCircuit` holds the same
+// types over its scalar field, so its inputs may be wider than 64 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DSAParams<T = u64> {
+    pub p: T,
+    pub q: T,
+    pub g: T,
+}
+
+impl DSAParams {
+    // The fields that differ as (name, self, other), in p, q, g order, for
+    // explaining why a proof made under one parameter set fails under another
+    pub fn diff(&self, other: &DSAParams) -> Vec<(&'static str, u64, u64)> {
+        [("p", self.p, other.p), ("q", self.q, other.q), ("g", self.g, other.g)]
+            .into_iter()
+            .filter(|(_, mine, theirs)| mine != theirs)
+            .collect()
+    }
+}
+
+// Private key x in [1, q) and public key y = g^x mod p
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyPair {
+    pub x: u64,
+    pub y: u64,
+}
+
+// A public key y = g^x mod p on its own, for callers that never see x
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey<T = u64>(pub T);
+
+impl From<&KeyPair> for PublicKey {
+    fn from(keys: &KeyPair) -> Self {
+        PublicKey(keys.y)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature<T = u64> {
+    pub r: T,
+    pub s: T,
+}
+
+impl DSAParams {
+    // The same parameters as elements of the circuit's scalar field
+    pub fn to_field<F: PrimeField>(&self) -> DSAParams<F> {
+        DSAParams { p: F::from(self.p), q: F::from(self.q), g: F::from(self.g) }
+    }
+}
+
+impl PublicKey {
+    pub fn to_field<F: PrimeField>(&self) -> PublicKey<F> {
+        PublicKey(F::from(self.0))
+    }
+}
+
+impl Signature {
+    pub fn to_field<F: PrimeField>(&self) -> Signature<F> {
+        Signature { r: F::from(self.r), s: F::from(self.s) }
+    }
+}
+
+fn random_prime(bits: u32, rng: &mut impl RngCore) -> u64 {
+    loop {
+        let candidate = rng.gen_range(1u64 << (bits - 1)..=u64::MAX >> (64 - bits));
+        if is_prime(candidate) {
+            return candidate;
+        }
+    }
+}
+
+// Smallest p width `gen_test_params` accepts. Its q has bits / 2 bits, so
+// this gives q >= 5; with q = 2 or 3 no nonce at all yields a signature with
+// both r and s non-zero, and `sign` could never succeed.
+pub const MIN_TEST_PARAM_BITS: u32 = 6;
+
+// Generates toy DSA parameters with a `bits`-bit p and a roughly half-width
+// q, plus a key pair under them. These are far too small to be secure and
+// exist so tests can produce genuine signatures instead of hand-picked tuples.
+pub fn gen_test_params(bits: u32, rng: &mut impl RngCore) -> (DSAParams, KeyPair) {
+    assert!(
+        (MIN_TEST_PARAM_BITS..=63).contains(&bits),
+        "p must have between {} and 63 bits",
+        MIN_TEST_PARAM_BITS
+    );
+    let q_bits = bits / 2;
+    let (p, q) = 'search: loop {
+        let q = random_prime(q_bits, rng);
+        // p = k * q + 1 must land in [2^(bits-1), 2^bits)
+        let k_min = ((1u64 << (bits - 1)) - 1).div_ceil(q);
+        let k_max = ((1u64 << bits) - 2) / q;
+        if k_min > k_max {
+            continue;
+        }
+        for _ in 0..64 {
+            let p = rng.gen_range(k_min..=k_max) * q + 1;
+            if is_prime(p) {
+                break 'search (p, q);
+            }
+        }
+    };
+    let g = find_generator(p, q).expect("Z_p^* has elements of order q when q | p - 1");
+    let params = DSAParams { p, q, g };
+    (params, keygen(&params, rng))
+}
+
+// Draws x uniformly from [1, q) and derives y = g^x mod p
+pub fn keygen(params: &DSAParams, rng: &mut impl RngCore) -> KeyPair {
+    let x = rng.gen_range(1..params.q);
+    KeyPair { x, y: modular_exponentiation(params.g, x, params.p) }
+}
+
+// Nonces `sign` draws before giving up. In the domains `gen_test_params`
+// produces at its smallest widths, checked exhaustively, every key and
+// message has a signing k among the q - 1 candidates, and the share of bad k
+// only shrinks as q grows, so running out of attempts there is negligible.
+const SIGN_ATTEMPTS: usize = 128;
+
+// Signs `msg` with a fresh nonce, hashing it exactly as
+// `DSAVerificationCircuit::from_message` does. Fails with `NoValidNonce`
+// if none of `SIGN_ATTEMPTS` nonces gives non-zero r and s, which for a
+// tiny or malformed domain may be every nonce, and with `QNotPrime` for
+// q < 2, where there is no nonce to draw.
+pub fn sign(
+    msg: &[u8],
+    sk: &KeyPair,
+    params: &DSAParams,
+    rng: &mut impl RngCore,
+) -> Result<Signature, ParamError> {
+    let DSAParams { p, q, g } = *params;
+    if q < 2 {
+        return Err(ParamError::QNotPrime);
+    }
+    let h = hash_to_scalar(&Sha256::digest(msg), q);
+    for _ in 0..SIGN_ATTEMPTS {
+        let k = rng.gen_range(1..q);
+        if let Some((r, s)) = dsa_sign_components(k, sk.x, h, p, q, g) {
+            return Ok(Signature { r, s });
+        }
+    }
+    Err(ParamError::NoValidNonce)
+}
+
+// Native DSA verification of `sig` on `msg` under the public key y, the
+// same check the circuit encodes
+pub fn verify(y: u64, msg: &[u8], sig: &Signature, params: &DSAParams) -> bool {
+    let DSAParams { p, q, g } = *params;
+    let h = hash_to_scalar(&Sha256::digest(msg), q);
+    dsa_verify_native(y, h, sig.r, sig.s, p, q, g)
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    // The circuit for the domain, the signer's key, the message hash and the
+    // signature, each moved into the scalar field
+    pub fn from_parts(params: &DSAParams, pk: PublicKey, h_x: u64, sig: &Signature) -> Self {
+        Self::from_field_parts(params.to_field(), pk.to_field(), F::from(h_x), sig.to_field())
+    }
+
+    // The circuit proving that `sig` verifies on `msg` under y, for
+    // signatures produced by `sign` or checked with `verify`
+    pub fn from_signature(y: u64, msg: &[u8], sig: &Signature, params: &DSAParams) -> Self {
+        let h_x = hash_to_scalar(&Sha256::digest(msg), params.q);
+        Self::from_parts(params, PublicKey(y), h_x, sig)
+    }
+
+    // The circuit's domain parameters as u64s, or `None` if one is wider
+    // than 64 bits
+    pub fn params(&self) -> Option<DSAParams> {
+        let narrow = |value: F| u64::try_from(Into::<BigUint>::into(value)).ok();
+        let DSAParams { p, q, g } = self.params;
+        Some(DSAParams { p: narrow(p)?, q: narrow(q)?, g: narrow(g)? })
+    }
+}
+
+impl<F: PrimeField> From<(DSAParams, PublicKey, u64, Signature)> for DSAVerificationCircuit<F> {
+    fn from((params, pk, h_x, sig): (DSAParams, PublicKey, u64, Signature)) -> Self {
+        Self::from_parts(&params, pk, h_x, &sig)
+    }
+}
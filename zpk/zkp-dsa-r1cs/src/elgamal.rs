@@ -0,0 +1,138 @@
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable};
+use num_bigint::BigUint;
+use crate::circuit::{to_field, Input};
+use crate::error::CircuitError;
+use crate::gadgets::{mul_mod, pow_mod};
+
+// ElGamal signature verification circuit: checks g^h(m) == y^r * r^s mod p.
+// Public inputs mirror the DSA circuit without q, since ElGamal works in the
+// full group Z_p^*.
+#[derive(Clone)]
+pub struct ElGamalVerificationCircuit<F: PrimeField = Fr> {
+    pub y: F,      // Public key
+    pub h_x: F,    // Message hash
+    pub r: F,      // Signature part r
+    pub s: F,      // Signature part s
+    pub p: F,      // Prime p
+    pub g: F,      // Generator g
+}
+
+// The three powers the circuit allocates as witnesses, computed natively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElGamalWitness {
+    pub g_h: BigUint, // g^h mod p
+    pub y_r: BigUint, // y^r mod p
+    pub r_s: BigUint, // r^s mod p
+}
+
+impl<F: PrimeField> ElGamalVerificationCircuit<F> {
+    pub fn new(y: u64, h_x: u64, r: u64, s: u64, p: u64, g: u64) -> Self {
+        ElGamalVerificationCircuit {
+            y: F::from(y),
+            h_x: F::from(h_x),
+            r: F::from(r),
+            s: F::from(s),
+            p: F::from(p),
+            g: F::from(g),
+        }
+    }
+
+    // Public inputs in the order they are allocated in `generate_constraints`
+    pub fn public_inputs(&self) -> Vec<F> {
+        vec![self.y, self.h_x, self.r, self.s, self.p, self.g]
+    }
+
+    // Fails with `ModulusTooSmall` for p < 2, as the DSA witness does
+    pub fn compute_witness(&self) -> Result<ElGamalWitness, CircuitError> {
+        let [y, h_x, r, s, p, g] = [self.y, self.h_x, self.r, self.s, self.p, self.g]
+            .map(Into::<BigUint>::into);
+        if p <= BigUint::from(1u32) {
+            return Err(CircuitError::ModulusTooSmall);
+        }
+        Ok(ElGamalWitness { g_h: g.modpow(&h_x, &p), y_r: y.modpow(&r, &p), r_s: r.modpow(&s, &p) })
+    }
+
+    // Allocates the inputs and lays down the constraints for `witness`,
+    // which is `compute_witness()` outside of soundness tests. Each power is
+    // derived from the inputs with `pow_mod` and its witness pinned to the
+    // result, so none of them can be chosen freely. Exponents are decomposed
+    // to the width of p, the range of h, r and s in a valid signature.
+    fn enforce(
+        self,
+        cs: &ConstraintSystemRef<F>,
+        witness: &ElGamalWitness,
+    ) -> Result<(), SynthesisError> {
+        let y = Input::alloc(cs, self.y)?;
+        let h_x = Input::alloc(cs, self.h_x)?;
+        let r = Input::alloc(cs, self.r)?;
+        let s = Input::alloc(cs, self.s)?;
+        let p = Input::alloc(cs, self.p)?;
+        let g = Input::alloc(cs, self.g)?;
+
+        let ElGamalWitness { g_h, y_r, r_s } = witness;
+        debug_log!("g_h_val: {}, y_r_val: {}, r_s_val: {}", g_h, y_r, r_s);
+
+        let exp_bits = p.val.bits() as usize;
+        let powers = [
+            (&g, &h_x, &witness.g_h),
+            (&y, &r, &witness.y_r),
+            (&r, &s, &witness.r_s),
+        ];
+        let mut vars = Vec::with_capacity(powers.len());
+        for (base, exp, value) in powers {
+            let power_var =
+                pow_mod(cs, base.var, &base.val, exp.var, &exp.val, exp_bits, p.var, &p.val)?;
+            let var = cs.new_witness_variable(|| Ok(to_field(value)))?;
+            cs.enforce_constraint(lc!() + var - power_var, lc!() + Variable::One, lc!())?;
+            vars.push(var);
+        }
+        let (g_h_var, y_r_var, r_s_var) = (vars[0], vars[1], vars[2]);
+
+        // Constraint: rhs = y_r * r_s mod p
+        let rhs_remainder_var =
+            mul_mod(cs, y_r_var, &witness.y_r, r_s_var, &witness.r_s, p.var, &p.val)?;
+
+        // Constraint: g_h == rhs
+        cs.enforce_constraint(
+            lc!() + g_h_var - rhs_remainder_var,
+            lc!() + Variable::One,
+            lc!(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for ElGamalVerificationCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let witness = self.compute_witness()?;
+        self.enforce(&cs, &witness)
+    }
+}
+
+// An `ElGamalVerificationCircuit` synthesized with a substituted witness,
+// the ElGamal counterpart of `WitnessOverrideCircuit`. Only built for tests
+// and with the `test-utils` feature.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Clone)]
+pub struct ElGamalWitnessOverrideCircuit<F: PrimeField = Fr> {
+    pub circuit: ElGamalVerificationCircuit<F>,
+    pub witness: ElGamalWitness,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<F: PrimeField> ElGamalVerificationCircuit<F> {
+    pub fn with_witness(self, witness: ElGamalWitness) -> ElGamalWitnessOverrideCircuit<F> {
+        ElGamalWitnessOverrideCircuit { circuit: self, witness }
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<F: PrimeField> ConstraintSynthesizer<F> for ElGamalWitnessOverrideCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.circuit.enforce(&cs, &self.witness)
+    }
+}
@@ -0,0 +1,253 @@
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::SerializationError;
+use num_bigint::BigUint;
+use std::fmt;
+use std::io;
+
+// Failures from the setup / prove / verify pipeline and from reading or
+// writing its artifacts
+pub enum PqZkpError {
+    Setup(SynthesisError),
+    Proving(SynthesisError),
+    Verification(SynthesisError),
+    InvalidParams(ParamError),
+    Circuit(CircuitError),
+    Serialization(SerializationError),
+    Io(io::Error),
+    // The verifying key was generated for a different number of public inputs
+    PublicInputCount { expected: usize, found: usize },
+    // A key or proof was written with a different artifact format version
+    VersionMismatch { expected: u8, found: u8 },
+}
+
+impl fmt::Display for PqZkpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PqZkpError::Setup(e) => write!(f, "setup failed: {}", e),
+            PqZkpError::Proving(e) => write!(f, "proving failed: {}", e),
+            PqZkpError::Verification(e) => write!(f, "verification failed: {}", e),
+            PqZkpError::InvalidParams(e) => write!(f, "invalid parameters: {}", e),
+            PqZkpError::Circuit(e) => write!(f, "invalid circuit input: {}", e),
+            PqZkpError::Serialization(e) => write!(f, "serialization failed: {}", e),
+            PqZkpError::Io(e) => write!(f, "I/O failed: {}", e),
+            PqZkpError::PublicInputCount { expected, found } => write!(
+                f,
+                "verifying key expects {} public inputs but {} were given",
+                expected, found
+            ),
+            PqZkpError::VersionMismatch { expected, found } => write!(
+                f,
+                "artifact has format version {} but this build reads version {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+// Returning an error from `main` prints it with `Debug`, so reuse the
+// human-readable message instead of the variant structure
+impl fmt::Debug for PqZkpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for PqZkpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PqZkpError::Setup(e) | PqZkpError::Proving(e) | PqZkpError::Verification(e) => Some(e),
+            PqZkpError::InvalidParams(e) => Some(e),
+            PqZkpError::Circuit(e) => Some(e),
+            PqZkpError::Serialization(e) => Some(e),
+            PqZkpError::Io(e) => Some(e),
+            PqZkpError::PublicInputCount { .. } | PqZkpError::VersionMismatch { .. } => None,
+        }
+    }
+}
+
+impl From<SerializationError> for PqZkpError {
+    fn from(e: SerializationError) -> Self {
+        match e {
+            SerializationError::IoError(e) => PqZkpError::Io(e),
+            other => PqZkpError::Serialization(other),
+        }
+    }
+}
+
+impl From<io::Error> for PqZkpError {
+    fn from(e: io::Error) -> Self {
+        PqZkpError::Io(e)
+    }
+}
+
+impl From<CircuitError> for PqZkpError {
+    fn from(e: CircuitError) -> Self {
+        PqZkpError::Circuit(e)
+    }
+}
+
+// Violations of the DSA domain relationships between circuit parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    QDoesNotDividePMinus1,
+    QNotPrime,
+    GeneratorWrongOrder,
+    SignatureOutOfRange,
+    // The named signature component is zero, which DSA never produces
+    SignatureComponentZero(&'static str),
+    // The named value is at least the scalar field modulus
+    FieldOverflow(&'static str),
+    // p is so wide that the product of two values below p can reach the
+    // scalar field modulus
+    ModulusTooWide,
+    // `sign` found no nonce giving non-zero r and s, as happens when q is
+    // tiny or the domain is malformed
+    NoValidNonce,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::QDoesNotDividePMinus1 => write!(f, "q does not divide p - 1"),
+            ParamError::QNotPrime => write!(f, "q is not prime"),
+            ParamError::GeneratorWrongOrder => write!(f, "g does not have order q modulo p"),
+            ParamError::SignatureOutOfRange => write!(f, "r and s must satisfy 0 < r, s < q"),
+            ParamError::SignatureComponentZero(name) => {
+                write!(f, "signature component `{}` is zero", name)
+            }
+            ParamError::FieldOverflow(name) => {
+                write!(f, "`{}` does not fit below the scalar field modulus", name)
+            }
+            ParamError::ModulusTooWide => {
+                write!(f, "p is too wide for products mod p to fit in the scalar field")
+            }
+            ParamError::NoValidNonce => write!(f, "no nonce gives a signature with r, s != 0"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+// Malformed circuit inputs, as opposed to generic R1CS failures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    NonInvertibleSignature,
+    // p is 0 or 1, so there is no group mod p to exponentiate in
+    ModulusTooSmall,
+    // `DSAVerificationCircuitBuilder::build` was called without this input
+    MissingField(&'static str),
+    // Strict mode found (g^u1 * y^u2 mod p) mod q != r mod q while computing
+    // the witness, so (r, s) is not a signature on h_x under y
+    SignatureMismatch { v_mod_q: BigUint, r_mod_q: BigUint },
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::NonInvertibleSignature => write!(f, "signature s is not invertible mod q"),
+            CircuitError::ModulusTooSmall => write!(f, "p must be at least 2"),
+            CircuitError::MissingField(name) => write!(f, "circuit input `{}` was not set", name),
+            CircuitError::SignatureMismatch { v_mod_q, r_mod_q } => write!(
+                f,
+                "v mod q = {} but r mod q = {}, so (r, s) is not a valid signature",
+                v_mod_q, r_mod_q
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+// Synthesis can only report arkworks errors, so inside `generate_constraints`
+// the distinction collapses to a missing assignment, except that a strict
+// mismatch means the system would be unsatisfiable
+impl From<CircuitError> for SynthesisError {
+    fn from(err: CircuitError) -> Self {
+        match err {
+            CircuitError::SignatureMismatch { .. } => SynthesisError::Unsatisfiable,
+            _ => SynthesisError::AssignmentMissing,
+        }
+    }
+}
+
+// Why `verify_or_explain` rejected a proof. A failed pairing check carries
+// no further detail: Groth16 cannot say which part of the statement was
+// wrong, only that the proof does not match the key and inputs.
+#[derive(Debug)]
+pub enum VerificationFailure {
+    // The verifying key was generated for a different number of public inputs
+    PublicInputCount { expected: usize, found: usize },
+    // The proof bytes could not be decoded, including a format version mismatch
+    MalformedProof(PqZkpError),
+    PairingCheckFailed,
+}
+
+impl fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationFailure::PublicInputCount { expected, found } => write!(
+                f,
+                "verifying key expects {} public inputs but {} were given",
+                expected, found
+            ),
+            VerificationFailure::MalformedProof(e) => write!(f, "malformed proof: {}", e),
+            VerificationFailure::PairingCheckFailed => {
+                write!(f, "pairing check failed: the proof is not valid for these inputs")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerificationFailure::MalformedProof(e) => Some(e),
+            VerificationFailure::PublicInputCount { .. }
+            | VerificationFailure::PairingCheckFailed => None,
+        }
+    }
+}
+
+// Malformed DER encoding of a `SEQUENCE { INTEGER r, INTEGER s }` signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerError {
+    Truncated,
+    UnexpectedTag { expected: u8, found: u8 },
+    NonCanonicalLength,
+    NonCanonicalInteger,
+    NegativeInteger,
+    TrailingData,
+    // A well-formed integer too large for the circuit's scalar field
+    InvalidParams(ParamError),
+}
+
+impl fmt::Display for DerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerError::Truncated => write!(f, "DER input ends before the encoded length"),
+            DerError::UnexpectedTag { expected, found } => {
+                write!(f, "expected DER tag {:#04x}, found {:#04x}", expected, found)
+            }
+            DerError::NonCanonicalLength => write!(f, "DER length is not minimally encoded"),
+            DerError::NonCanonicalInteger => write!(f, "DER integer has redundant leading zeros"),
+            DerError::NegativeInteger => write!(f, "signature integers must be positive"),
+            DerError::TrailingData => write!(f, "unexpected bytes after the DER signature"),
+            DerError::InvalidParams(e) => write!(f, "invalid signature value: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DerError::InvalidParams(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParamError> for DerError {
+    fn from(e: ParamError) -> Self {
+        DerError::InvalidParams(e)
+    }
+}
@@ -0,0 +1,44 @@
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use std::str;
+use crate::prover::verify_serialized;
+
+fn bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: the caller guarantees `ptr` points to `len` readable bytes
+    Some(unsafe { slice::from_raw_parts(ptr, len) })
+}
+
+// C entry point around `verify_serialized`, declared in `ffi/pq_zkp.h`.
+// The key and proof use the `save_keys` / `proof_to_bytes` encodings and
+// the inputs are UTF-8 `DSAInputs` JSON. Returns 1 for a valid proof, 0 for
+// an invalid one and -1 for null pointers or malformed data. Panics are
+// caught so they never unwind into C.
+//
+// Safety: each pointer must be valid for reads of its length for the
+// duration of the call.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn pq_zkp_verify(
+    vk_ptr: *const u8,
+    vk_len: usize,
+    inputs_ptr: *const u8,
+    inputs_len: usize,
+    proof_ptr: *const u8,
+    proof_len: usize,
+) -> c_int {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let vk = bytes(vk_ptr, vk_len)?;
+        let inputs = str::from_utf8(bytes(inputs_ptr, inputs_len)?).ok()?;
+        let proof = bytes(proof_ptr, proof_len)?;
+        verify_serialized(vk, inputs, proof).ok()
+    }));
+    match result {
+        Ok(Some(true)) => 1,
+        Ok(Some(false)) => 0,
+        _ => -1,
+    }
+}
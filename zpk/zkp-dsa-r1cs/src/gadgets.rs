@@ -0,0 +1,375 @@
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError, Variable};
+use num_bigint::BigUint;
+use num_traits::One;
+
+// Decomposes `value` into `num_bits` little-endian boolean witnesses and
+// enforces that their weighted sum equals `value`. The bits are returned so
+// callers can reuse them.
+pub fn enforce_bits<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    value: Variable,
+    num_bits: usize,
+) -> Result<Vec<Variable>, SynthesisError> {
+    let value_repr = cs.assigned_value(value).map(|v| v.into_repr());
+    let mut bits = Vec::with_capacity(num_bits);
+    let mut sum = lc!();
+    let mut coeff = F::one();
+    for i in 0..num_bits {
+        let bit_var = cs.new_witness_variable(|| {
+            let repr = value_repr.ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(if repr.get_bit(i) { F::one() } else { F::zero() })
+        })?;
+        // bit * (1 - bit) = 0
+        cs.enforce_constraint(lc!() + bit_var, lc!() + Variable::One - bit_var, lc!())?;
+        sum += (coeff, bit_var);
+        coeff.double_in_place();
+        bits.push(bit_var);
+    }
+    cs.enforce_constraint(sum, lc!() + Variable::One, lc!() + value)?;
+    Ok(bits)
+}
+
+// `enforce_bits` over the full width of `F`, additionally enforcing that
+// the bits read as an integer below the field modulus. Without that check a
+// value v < 2^bits - modulus also decomposes as v + modulus, so the bits
+// would not be a function of the value. Walking the bits of modulus - 1 from
+// the top, `run` is 1 while the bits so far equal its prefix; wherever
+// modulus - 1 has a 0, the value's bit must be 0 while `run` is 1. That is
+// about one constraint per bit on top of the decomposition.
+pub fn enforce_canonical_bits<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    value: Variable,
+) -> Result<Vec<Variable>, SynthesisError> {
+    let num_bits = F::size_in_bits();
+    let bits = enforce_bits(cs, value, num_bits)?;
+    let mut max = F::Params::MODULUS;
+    max.sub_noborrow(&F::BigInt::from(1));
+
+    let and = |a: Variable, b: Variable| -> Result<Variable, SynthesisError> {
+        let a_val = cs.assigned_value(a);
+        let b_val = cs.assigned_value(b);
+        let product = cs.new_witness_variable(|| {
+            Ok(a_val.ok_or(SynthesisError::AssignmentMissing)?
+                * b_val.ok_or(SynthesisError::AssignmentMissing)?)
+        })?;
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+        Ok(product)
+    };
+    let mut run: Option<Variable> = None;
+    for i in (0..num_bits).rev() {
+        if max.get_bit(i) {
+            run = Some(match run {
+                Some(run) => and(run, bits[i])?,
+                None => bits[i],
+            });
+        } else {
+            // bit * run = 0, or bit = 0 above the first 1 of modulus - 1
+            let guard = run.map_or(lc!() + Variable::One, |run| lc!() + run);
+            cs.enforce_constraint(lc!() + bits[i], guard, lc!())?;
+        }
+    }
+    Ok(bits)
+}
+
+// Enforces a < b by showing b - a - 1 is representable in `bit_width` bits.
+// Assumes both `a` and `b` are already known to fit in `bit_width` bits;
+// otherwise the field subtraction can wrap and the check is meaningless.
+pub fn enforce_less_than<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    a: Variable,
+    b: Variable,
+    bit_width: usize,
+) -> Result<(), SynthesisError> {
+    let a_val = cs.assigned_value(a);
+    let b_val = cs.assigned_value(b);
+    let gap_var = cs.new_witness_variable(|| {
+        let a_val = a_val.ok_or(SynthesisError::AssignmentMissing)?;
+        let b_val = b_val.ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(b_val - a_val - F::one())
+    })?;
+    cs.enforce_constraint(
+        lc!() + b - a - Variable::One,
+        lc!() + Variable::One,
+        lc!() + gap_var,
+    )?;
+    enforce_bits(cs, gap_var, bit_width)?;
+    Ok(())
+}
+
+// Enforces 0 <= remainder < modulus by bounding the remainder to the bit
+// width of the modulus and then comparing the two
+pub fn enforce_remainder_range<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    remainder_var: Variable,
+    modulus_var: Variable,
+    modulus_val: &BigUint,
+) -> Result<(), SynthesisError> {
+    let num_bits = modulus_val.bits() as usize;
+    enforce_bits(cs, remainder_var, num_bits)?;
+    enforce_less_than(cs, remainder_var, modulus_var, num_bits)
+}
+
+// Width of the quotient of a `dividend_bits`-bit dividend by `modulus_val`.
+// The dividend is below 2^dividend_bits and the modulus at least
+// 2^(bits - 1), so the quotient is below 2^(dividend_bits - bits + 1), and
+// modulus * quotient + remainder stays below 2^(dividend_bits + 1). Bounding
+// the quotient to this width is what makes the reduction sound: if that sum
+// never reaches the field characteristic it equals the dividend as an
+// integer, so the remainder is the integer remainder. Left unbounded, the
+// quotient (dividend - remainder) / modulus in the field exists for every
+// remainder.
+fn quotient_bits<F: PrimeField>(
+    dividend_bits: usize,
+    modulus_val: &BigUint,
+) -> Result<usize, SynthesisError> {
+    if dividend_bits + 1 >= F::Params::MODULUS_BITS as usize {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    Ok((dividend_bits + 1).saturating_sub(modulus_val.bits() as usize))
+}
+
+// Reduces `dividend` modulo `modulus_var`: allocates the remainder and
+// quotient witnesses, enforces dividend = modulus * quotient + remainder,
+// range checks both and returns the remainder. The caller supplies the
+// dividend and modulus values so the witnesses are computed in integers
+// rather than in the field, and a bound of `dividend_bits` on the dividend,
+// which sizes the quotient check. A dividend above that bound leaves the
+// system unsatisfied, and one so wide that the sum could reach the field
+// characteristic fails synthesis with `Unsatisfiable`.
+pub fn mod_reduce<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    dividend: Variable,
+    dividend_val: &BigUint,
+    dividend_bits: usize,
+    modulus_var: Variable,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    let quotient_bits = quotient_bits::<F>(dividend_bits, modulus_val)?;
+    let quotient_val = dividend_val / modulus_val;
+    let remainder_var = cs.new_witness_variable(|| Ok(F::from(dividend_val % modulus_val)))?;
+    let quotient_var = cs.new_witness_variable(|| Ok(F::from(quotient_val.clone())))?;
+    let modulus_times_quotient_var =
+        cs.new_witness_variable(|| Ok(F::from(modulus_val * &quotient_val)))?;
+    cs.enforce_constraint(lc!() + modulus_var, lc!() + quotient_var, lc!() + modulus_times_quotient_var)?;
+    cs.enforce_constraint(
+        lc!() + dividend - modulus_times_quotient_var,
+        lc!() + Variable::One,
+        lc!() + remainder_var,
+    )?;
+    enforce_bits(cs, quotient_var, quotient_bits)?;
+    enforce_remainder_range(cs, remainder_var, modulus_var, modulus_val)?;
+    Ok(remainder_var)
+}
+
+// Enforces product = a * b and returns the product reduced modulo
+// `modulus_var` via `mod_reduce`. Both `a` and `b` must be known to fit in
+// the bit width of the modulus, as the remainders of the other gadgets do;
+// the product then has at most twice that width and cannot wrap in the
+// field, which `mod_reduce` checks against the field size.
+pub fn mul_mod<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    a: Variable,
+    a_val: &BigUint,
+    b: Variable,
+    b_val: &BigUint,
+    modulus_var: Variable,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    let product_val = a_val * b_val;
+    let product_var = cs.new_witness_variable(|| Ok(F::from(product_val.clone())))?;
+    cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product_var)?;
+    let product_bits = 2 * modulus_val.bits() as usize;
+    mod_reduce(cs, product_var, &product_val, product_bits, modulus_var, modulus_val)
+}
+
+// Enforces 0 <= remainder < modulus for a constant modulus of n bits: the
+// remainder and modulus - 1 - remainder must both fit in n bits. Like
+// `enforce_remainder_range` this is 2n + 3 constraints.
+pub fn enforce_remainder_range_const<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    remainder_var: Variable,
+    modulus_val: &BigUint,
+) -> Result<(), SynthesisError> {
+    let num_bits = modulus_val.bits() as usize;
+    enforce_bits(cs, remainder_var, num_bits)?;
+    let max = F::from(modulus_val - BigUint::one());
+    let remainder_val = cs.assigned_value(remainder_var);
+    let gap_var = cs.new_witness_variable(|| {
+        Ok(max - remainder_val.ok_or(SynthesisError::AssignmentMissing)?)
+    })?;
+    cs.enforce_constraint(
+        lc!() + (max, Variable::One) - remainder_var,
+        lc!() + Variable::One,
+        lc!() + gap_var,
+    )?;
+    enforce_bits(cs, gap_var, num_bits)?;
+    Ok(())
+}
+
+// `mod_reduce` for a modulus fixed when the circuit is built rather than
+// passed as a public input. modulus * quotient becomes a scalar multiple of
+// the quotient, so the product witness and its constraint disappear and
+// dividend = modulus * quotient + remainder is a single linear constraint.
+// The quotient is range checked as in `mod_reduce`, so for an n-bit modulus
+// this costs one constraint less than `mod_reduce` with the same bound.
+pub fn mod_reduce_const<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    dividend: Variable,
+    dividend_val: &BigUint,
+    dividend_bits: usize,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    let quotient_bits = quotient_bits::<F>(dividend_bits, modulus_val)?;
+    let remainder_var = cs.new_witness_variable(|| Ok(F::from(dividend_val % modulus_val)))?;
+    let quotient_var = cs.new_witness_variable(|| Ok(F::from(dividend_val / modulus_val)))?;
+    cs.enforce_constraint(
+        lc!() + dividend - (F::from(modulus_val.clone()), quotient_var),
+        lc!() + Variable::One,
+        lc!() + remainder_var,
+    )?;
+    enforce_bits(cs, quotient_var, quotient_bits)?;
+    enforce_remainder_range_const(cs, remainder_var, modulus_val)?;
+    Ok(remainder_var)
+}
+
+// `mul_mod` for a constant modulus, with the same bounds on `a` and `b`.
+// With the modulus a scalar, the single constraint
+// a * b = modulus * quotient + remainder replaces the separate product and
+// reduction, saving two constraints over `mul_mod`.
+pub fn mul_mod_const<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    a: Variable,
+    a_val: &BigUint,
+    b: Variable,
+    b_val: &BigUint,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    let quotient_bits = quotient_bits::<F>(2 * modulus_val.bits() as usize, modulus_val)?;
+    let product_val = a_val * b_val;
+    let remainder_var = cs.new_witness_variable(|| Ok(F::from(&product_val % modulus_val)))?;
+    let quotient_var = cs.new_witness_variable(|| Ok(F::from(&product_val / modulus_val)))?;
+    cs.enforce_constraint(
+        lc!() + a,
+        lc!() + b,
+        lc!() + (F::from(modulus_val.clone()), quotient_var) + remainder_var,
+    )?;
+    enforce_bits(cs, quotient_var, quotient_bits)?;
+    enforce_remainder_range_const(cs, remainder_var, modulus_val)?;
+    Ok(remainder_var)
+}
+
+// Constrains base^exp mod modulus by right-to-left square-and-multiply over
+// the `num_bits` little-endian bits of `exp`. The bits come from
+// `enforce_bits`, so they are bound to `exp` and the system is only
+// satisfiable when exp < 2^num_bits. Likewise the base must fit in the bit
+// width of the modulus.
+#[allow(clippy::too_many_arguments)]
+pub fn pow_mod<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    base: Variable,
+    base_val: &BigUint,
+    exp: Variable,
+    exp_val: &BigUint,
+    num_bits: usize,
+    modulus_var: Variable,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    pow_mod_with(cs, base, base_val, exp, exp_val, num_bits, Some(modulus_var), modulus_val)
+}
+
+// `pow_mod` for a constant modulus, built from `mod_reduce_const` and
+// `mul_mod_const`. Each exponent bit saves 4 constraints, 2 in the
+// multiplication and 2 in the squaring (the last bit has no squaring), and
+// the initial reduction of the base saves one more.
+pub fn pow_mod_const<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    base: Variable,
+    base_val: &BigUint,
+    exp: Variable,
+    exp_val: &BigUint,
+    num_bits: usize,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    pow_mod_with(cs, base, base_val, exp, exp_val, num_bits, None, modulus_val)
+}
+
+// The reduction gadgets for a modulus given as a variable, or as a
+// constant when `modulus_var` is `None`
+pub(crate) fn reduce<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    dividend: Variable,
+    dividend_val: &BigUint,
+    dividend_bits: usize,
+    modulus_var: Option<Variable>,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    match modulus_var {
+        Some(modulus_var) => {
+            mod_reduce(cs, dividend, dividend_val, dividend_bits, modulus_var, modulus_val)
+        }
+        None => mod_reduce_const(cs, dividend, dividend_val, dividend_bits, modulus_val),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn multiply<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    a: Variable,
+    a_val: &BigUint,
+    b: Variable,
+    b_val: &BigUint,
+    modulus_var: Option<Variable>,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    match modulus_var {
+        Some(modulus_var) => mul_mod(cs, a, a_val, b, b_val, modulus_var, modulus_val),
+        None => mul_mod_const(cs, a, a_val, b, b_val, modulus_val),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pow_mod_with<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    base: Variable,
+    base_val: &BigUint,
+    exp: Variable,
+    exp_val: &BigUint,
+    num_bits: usize,
+    modulus_var: Option<Variable>,
+    modulus_val: &BigUint,
+) -> Result<Variable, SynthesisError> {
+    let bits = enforce_bits(cs, exp, num_bits)?;
+    let modulus_bits = modulus_val.bits() as usize;
+    let mut power_var = reduce(cs, base, base_val, modulus_bits, modulus_var, modulus_val)?;
+    let mut power_val = base_val % modulus_val;
+
+    // Starts from the constant 1 so an empty exponent still yields a variable
+    let mut acc_val = BigUint::one() % modulus_val;
+    let mut acc_var = cs.new_witness_variable(|| Ok(F::from(acc_val.clone())))?;
+    cs.enforce_constraint(
+        lc!() + acc_var,
+        lc!() + Variable::One,
+        lc!() + (F::from(acc_val.clone()), Variable::One),
+    )?;
+
+    for (i, bit_var) in bits.into_iter().enumerate() {
+        // factor = bit ? power : 1, enforced as bit * (power - 1) = factor - 1
+        let factor_val = if exp_val.bit(i as u64) { power_val.clone() } else { BigUint::one() };
+        let factor_var = cs.new_witness_variable(|| Ok(F::from(factor_val.clone())))?;
+        cs.enforce_constraint(
+            lc!() + bit_var,
+            lc!() + power_var - Variable::One,
+            lc!() + factor_var - Variable::One,
+        )?;
+        acc_var = multiply(cs, acc_var, &acc_val, factor_var, &factor_val, modulus_var, modulus_val)?;
+        acc_val = &acc_val * &factor_val % modulus_val;
+
+        if i + 1 < num_bits {
+            power_var = multiply(cs, power_var, &power_val, power_var, &power_val, modulus_var, modulus_val)?;
+            power_val = &power_val * &power_val % modulus_val;
+        }
+    }
+    Ok(acc_var)
+}
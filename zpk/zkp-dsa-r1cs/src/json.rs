@@ -0,0 +1,81 @@
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use crate::circuit::{check_fits, DSAVerificationCircuit};
+use crate::dsa::{DSAParams, PublicKey, Signature};
+
+// Circuit inputs as decimal strings, since real DSA parameters exceed the
+// range of JSON numbers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DSAInputs {
+    pub y: String,
+    pub h_x: String,
+    pub r: String,
+    pub s: String,
+    pub p: String,
+    pub q: String,
+    pub g: String,
+}
+
+// Rejects values at or above the field modulus rather than reducing them
+fn parse_field<F: PrimeField>(name: &'static str, value: &str) -> io::Result<F> {
+    let value = BigUint::from_str(value).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("field `{}` is not a decimal integer: {}", name, e),
+        )
+    })?;
+    check_fits::<F>(name, &value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(F::from(value))
+}
+
+fn format_field<F: PrimeField>(value: &F) -> String {
+    let value: BigUint = (*value).into();
+    value.to_string()
+}
+
+impl DSAInputs {
+    pub fn to_circuit<F: PrimeField>(&self) -> io::Result<DSAVerificationCircuit<F>> {
+        Ok(DSAVerificationCircuit::from_field_parts(
+            DSAParams {
+                p: parse_field("p", &self.p)?,
+                q: parse_field("q", &self.q)?,
+                g: parse_field("g", &self.g)?,
+            },
+            PublicKey(parse_field("y", &self.y)?),
+            parse_field("h_x", &self.h_x)?,
+            Signature { r: parse_field("r", &self.r)?, s: parse_field("s", &self.s)? },
+        ))
+    }
+}
+
+impl<F: PrimeField> From<&DSAVerificationCircuit<F>> for DSAInputs {
+    fn from(circuit: &DSAVerificationCircuit<F>) -> Self {
+        DSAInputs {
+            y: format_field(&circuit.key.0),
+            h_x: format_field(&circuit.h_x),
+            r: format_field(&circuit.sig.r),
+            s: format_field(&circuit.sig.s),
+            p: format_field(&circuit.params.p),
+            q: format_field(&circuit.params.q),
+            g: format_field(&circuit.params.g),
+        }
+    }
+}
+
+impl<F: PrimeField> DSAVerificationCircuit<F> {
+    pub fn from_json(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let inputs: DSAInputs = serde_json::from_str(&contents)?;
+        inputs.to_circuit()
+    }
+
+    pub fn to_json(&self, path: &Path) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(&DSAInputs::from(self))?;
+        fs::write(path, contents)
+    }
+}
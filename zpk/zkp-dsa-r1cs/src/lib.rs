@@ -0,0 +1,91 @@
+// Without the `std` feature only the integer helpers in `utils` are built,
+// so they can be reused in `no_std` code (`BigUint` still needs `alloc`)
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod circuit;
+#[cfg(feature = "std")]
+pub mod der;
+#[cfg(feature = "std")]
+pub mod dsa;
+#[cfg(feature = "std")]
+pub mod elgamal;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "cffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod gadgets;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "marlin")]
+pub mod marlin;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
+#[cfg(feature = "std")]
+pub mod prover;
+#[cfg(feature = "std")]
+pub mod rerandomize;
+#[cfg(feature = "std")]
+pub mod serialization;
+#[cfg(feature = "solidity")]
+pub mod solidity;
+pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
+
+#[cfg(feature = "std")]
+pub use crate::{
+    analysis::{
+        check_satisfied, constraint_matrices, constraint_stats, dry_run_setup, estimate_memory,
+        r1cs_nnz, ConstraintStats, MemoryEstimate,
+    },
+    batch::{BatchDSACircuit, DSASig},
+    circuit::{
+        Bls12DSACircuit, DSAVerificationCircuit, DSAVerificationCircuitBuilder, DSAWitness,
+        FixedDomainCircuit,
+    },
+    der::parse_der_signature,
+    dsa::{
+        gen_test_params, keygen, sign, verify, DSAParams, KeyPair, PublicKey, Signature,
+        MIN_TEST_PARAM_BITS,
+    },
+    elgamal::{ElGamalVerificationCircuit, ElGamalWitness},
+    error::{CircuitError, DerError, ParamError, PqZkpError, VerificationFailure},
+    json::DSAInputs,
+    prover::{
+        derive_rng, expected_public_inputs, prepare_public_inputs, prepare_vk, prove_and_verify,
+        prove_many, prove_proof, seeded_rng, setup_keys, verify_batch, verify_from_files,
+        verify_or_explain, verify_prepared, verify_serialized, verify_stream,
+        verify_with_prepared_inputs, DSAProver, Groth16Prover, ProverConfig,
+    },
+    rerandomize::{
+        rerandomize_delta, setup_with_delta_updates, verify_delta_update, DeltaUpdate,
+    },
+    serialization::{
+        load_keys, load_proving_key, load_verifying_key, proof_from_bytes, proof_to_bytes,
+        public_inputs_from_bytes, public_inputs_to_bytes, read_proof_frame, save_keys,
+        verifying_key_from_bytes, verifying_key_to_bytes, write_proof_frame, FORMAT_VERSION,
+        MAX_PROOF_FRAME,
+    },
+};
+#[cfg(feature = "marlin")]
+pub use crate::marlin::{MarlinError, MarlinIpaProver};
+#[cfg(feature = "poseidon")]
+pub use crate::poseidon::{poseidon_digest, PoseidonDSACircuit};
+#[cfg(all(feature = "std", any(test, feature = "test-utils")))]
+pub use crate::circuit::{WitnessOverrideCircuit, WitnessOverrides};
+#[cfg(all(feature = "std", any(test, feature = "test-utils")))]
+pub use crate::elgamal::ElGamalWitnessOverrideCircuit;
+pub use utils::{extended_gcd, modular_exponentiation, modular_inverse};
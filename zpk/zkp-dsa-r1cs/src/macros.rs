@@ -0,0 +1,13 @@
+// Internal logging shim: forwards to `log::debug!` unless `log` is disabled
+// or the `silent` feature is on. The silent expansion still type-checks
+// its arguments, so toggling the feature can't introduce unused warnings.
+macro_rules! debug_log {
+    ($($arg:tt)*) => {{
+        #[cfg(all(feature = "log", not(feature = "silent")))]
+        log::debug!($($arg)*);
+        #[cfg(not(all(feature = "log", not(feature = "silent"))))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    }};
+}
@@ -1,45 +1,264 @@
-use ark_bls12_381::{Bls12_381, Fr};
+use ark_bls12_381::Bls12_381;
+use ark_ec::PairingEngine;
 use ark_groth16::Groth16;
 use ark_snark::SNARK;
-use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
-use crate::circuit::DSAVerificationCircuit;
+use ark_ff::PrimeField;
+use clap::{Args, Parser, Subcommand};
+use num_bigint::BigUint;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+use zkp_dsa_r1cs::{
+    gen_test_params, load_proving_key, load_verifying_key, proof_from_bytes, proof_to_bytes,
+    prove_proof, save_keys, seeded_rng, setup_keys, sign, DSAVerificationCircuit, PqZkpError,
+    MIN_TEST_PARAM_BITS,
+};
 
-mod circuit;
-mod utils;
+// The message `params gen` signs. The parameter file only records its hash.
+const SAMPLE_MESSAGE: &[u8] = b"pq-zkp sample message";
+
+// Without a subcommand the binary runs the built-in example end to end
+#[derive(Parser)]
+#[command(about = "Groth16 proofs of DSA signature verification")]
+struct Cli {
+    /// Fixed RNG seed for reproducible keys and proofs; defaults to OS entropy
+    #[arg(long, global = true, env = "PQ_ZKP_SEED")]
+    seed: Option<u64>,
+    /// Print verification results as a single JSON object instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(flatten)]
+    example: ExampleParams,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+// Parameters for the built-in example, overridable from the environment so
+// different sets can be tried without recompiling
+#[derive(Args)]
+struct ExampleParams {
+    /// Public key y
+    #[arg(long, env = "PQ_ZKP_Y", default_value_t = 3)]
+    y: u64,
+    /// Message hash h(x)
+    #[arg(long, env = "PQ_ZKP_H_X", default_value_t = 2)]
+    h_x: u64,
+    /// Signature part r
+    #[arg(long, env = "PQ_ZKP_R", default_value_t = 2)]
+    r: u64,
+    /// Signature part s
+    #[arg(long, env = "PQ_ZKP_S", default_value_t = 2)]
+    s: u64,
+    /// Prime modulus p
+    #[arg(long, env = "PQ_ZKP_P", default_value_t = 7)]
+    p: u64,
+    /// Subgroup order q
+    #[arg(long, env = "PQ_ZKP_Q", default_value_t = 3)]
+    q: u64,
+    /// Generator g
+    #[arg(long, env = "PQ_ZKP_G", default_value_t = 3)]
+    g: u64,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate proving and verification keys for a parameter set
+    Setup {
+        #[arg(long)]
+        params: PathBuf,
+        #[arg(long)]
+        out_dir: PathBuf,
+    },
+    /// Prove that the signature in the parameter file verifies
+    Prove {
+        #[arg(long)]
+        pk: PathBuf,
+        #[arg(long)]
+        params: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Verify a proof; exits with 0 when valid, 1 when invalid and 2 on error
+    Verify {
+        #[arg(long)]
+        vk: PathBuf,
+        #[arg(long)]
+        params: PathBuf,
+        #[arg(long)]
+        proof: PathBuf,
+        /// Parameter file the proof was generated from; if the proof does not
+        /// verify, the parameters that differ from it are listed on stderr
+        #[arg(long)]
+        prover_params: Option<PathBuf>,
+    },
+    /// Work with parameter files
+    Params {
+        #[command(subcommand)]
+        command: ParamsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ParamsCommand {
+    /// Generate toy DSA parameters, a key pair and a signature on a sample
+    /// message, written as a parameter file the other subcommands accept
+    Gen {
+        /// Bit length of the prime modulus p, from 6 to 63
+        #[arg(
+            long,
+            default_value_t = 16,
+            value_parser = clap::value_parser!(u32).range(i64::from(MIN_TEST_PARAM_BITS)..=63)
+        )]
+        bits: u32,
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+// The `--json` output. Fields are only ever added, so scripts can rely on
+// the existing ones: `public_inputs` are decimal strings in
+// `DSAVerificationCircuit::public_inputs` order, and `duration_ms` is the
+// wall time of the whole command.
+#[derive(Serialize)]
+struct VerificationReport {
+    valid: bool,
+    public_inputs: Vec<String>,
+    duration_ms: u128,
+}
+
+fn report<F: PrimeField>(json: bool, valid: bool, public_inputs: &[F], start: Instant) {
+    if json {
+        let report = VerificationReport {
+            valid,
+            public_inputs: public_inputs
+                .iter()
+                .map(|&input| Into::<BigUint>::into(input).to_string())
+                .collect(),
+            duration_ms: start.elapsed().as_millis(),
+        };
+        println!("{}", serde_json::to_string(&report).expect("Report is serializable"));
+    } else {
+        println!("Proof verification result: {}", valid);
+    }
+}
+
+// Generic over the pairing engine so the example can run on any curve
+fn run_example<E: PairingEngine>(
+    params: &ExampleParams,
+    seed: Option<u64>,
+    json: bool,
+) -> Result<bool, PqZkpError> {
+    let start = Instant::now();
 
-fn main() {
     // Initialize random number generator
-    let mut rng = StdRng::seed_from_u64(0u64);
-
-    // Example inputs: p=7, q=3, g=3, y=3, h(x)=2, r=2, s=2
-    let circuit = DSAVerificationCircuit {
-        y: Fr::from(3u64),    // Public key
-        h_x: Fr::from(2u64),  // Message hash
-        r: Fr::from(2u64),    // Signature r
-        s: Fr::from(2u64),    // Signature s
-        p: Fr::from(7u64),    // Prime p
-        q: Fr::from(3u64),    // Prime q
-        g: Fr::from(3u64),    // Generator g
-    };
+    let mut rng = seeded_rng(seed);
+
+    // Defaults to p=7, q=3, g=3, y=3, h(x)=2, r=2, s=2
+    let circuit = DSAVerificationCircuit::<E::Fr>::new(
+        params.y, params.h_x, params.r, params.s, params.p, params.q, params.g,
+    );
 
     // Generate proving and verification keys
-    let pk_vk = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).expect("Setup failed");
-    let (pk, vk) = pk_vk;
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .map_err(PqZkpError::Setup)?;
 
     // Generate proof
-    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).expect("Proving failed");
+    let proof = Groth16::<E>::prove(&pk, circuit.clone(), &mut rng).map_err(PqZkpError::Proving)?;
 
     // Verify proof
-    let public_inputs = vec![
-        circuit.y,
-        circuit.h_x,
-        circuit.r,
-        circuit.s,
-        circuit.p,
-        circuit.q,
-        circuit.g,
-    ];
-    let is_valid = Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof).expect("Verification failed");
-
-    println!("Proof verification result: {}", is_valid);
+    let public_inputs = circuit.public_inputs();
+    let is_valid = Groth16::<E>::verify(&vk, &public_inputs, &proof)
+        .map_err(PqZkpError::Verification)?;
+
+    report(json, is_valid, &public_inputs, start);
+    Ok(is_valid)
+}
+
+// Lists on stderr how the verifier's inputs differ from the prover's, the
+// usual suspect when a proof fails to verify. Nothing is printed when the
+// inputs agree, since then the proof itself is at fault.
+fn explain_mismatch<F: PrimeField>(
+    verifier: &DSAVerificationCircuit<F>,
+    prover: &DSAVerificationCircuit<F>,
+) {
+    if verifier.public_inputs() == prover.public_inputs() {
+        return;
+    }
+    eprintln!("public inputs differ from the prover's parameter file");
+    match (verifier.params(), prover.params()) {
+        (Some(mine), Some(theirs)) => {
+            let diff = mine.diff(&theirs);
+            if diff.is_empty() {
+                eprintln!("  p, q and g agree, so the key, message hash or signature differs");
+            }
+            for (name, mine, theirs) in diff {
+                eprintln!("  {}: {} here, {} for the prover", name, mine, theirs);
+            }
+        }
+        _ => eprintln!("  (domain parameters wider than 64 bits are not compared)"),
+    }
+}
+
+// Runs the selected command and reports whether it succeeded. Only
+// verification can produce `false`; every other failure is an error.
+fn run(cli: Cli) -> Result<bool, PqZkpError> {
+    let (seed, json) = (cli.seed, cli.json);
+    let start = Instant::now();
+
+    match cli.command {
+        None => run_example::<Bls12_381>(&cli.example, seed, json),
+        Some(Command::Setup { params, out_dir }) => {
+            let circuit = DSAVerificationCircuit::from_json(&params)?;
+            let (pk, vk) = setup_keys(&circuit, seed)?;
+            save_keys(&pk, &vk, &out_dir)?;
+            Ok(true)
+        }
+        Some(Command::Prove { pk, params, out }) => {
+            let circuit = DSAVerificationCircuit::from_json(&params)?;
+            let pk = load_proving_key(&pk)?;
+            let proof = prove_proof(&pk, &circuit, seed)?;
+            fs::write(&out, proof_to_bytes(&proof))?;
+            Ok(true)
+        }
+        Some(Command::Verify { vk, params, proof, prover_params }) => {
+            let circuit = DSAVerificationCircuit::from_json(&params)?;
+            let vk = load_verifying_key(&vk)?;
+            let proof = proof_from_bytes(&fs::read(&proof)?)?;
+            let public_inputs = circuit.public_inputs();
+            let is_valid = Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof)
+                .map_err(PqZkpError::Verification)?;
+            report(json, is_valid, &public_inputs, start);
+            if let (false, Some(path)) = (is_valid, prover_params) {
+                explain_mismatch(&circuit, &DSAVerificationCircuit::from_json(&path)?);
+            }
+            Ok(is_valid)
+        }
+        Some(Command::Params { command: ParamsCommand::Gen { bits, out } }) => {
+            let mut rng = seeded_rng(seed);
+            let (params, key) = gen_test_params(bits, &mut rng);
+            let sig =
+                sign(SAMPLE_MESSAGE, &key, &params, &mut rng).map_err(PqZkpError::InvalidParams)?;
+            let circuit =
+                DSAVerificationCircuit::<<Bls12_381 as PairingEngine>::Fr>::from_signature(
+                    key.y, SAMPLE_MESSAGE, &sig, &params,
+                );
+            circuit.to_json(&out)?;
+            Ok(true)
+        }
+    }
+}
+
+// Exit codes: 0 for success, 1 for a proof that fails to verify, 2 for
+// any error, which is reported on stderr
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
 }
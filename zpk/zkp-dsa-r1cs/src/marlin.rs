@@ -0,0 +1,79 @@
+use ark_bls12_381::{Fr, G1Affine};
+use ark_marlin::ahp::Error as AHPError;
+use ark_marlin::{IndexProverKey, IndexVerifierKey, Marlin, Proof};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::ipa_pc::InnerProductArgPC;
+use ark_std::rand::{CryptoRng, RngCore};
+use blake2::Blake2s;
+use crate::analysis::constraint_matrices;
+use crate::circuit::DSAVerificationCircuit;
+use crate::prover::{seeded_rng, DSAProver};
+
+// A second backend with no trusted setup: Marlin over the inner-product
+// argument commitment scheme on the BLS12-381 G1 group. The commitment key
+// is a list of generators hashed to the curve from a public label, so setup
+// samples no secret at all, the `rng` passed to it is ignored, and anyone can
+// recompute and check the keys. Marlin's indexing then derives the proving
+// and verifying keys from that key and the circuit, again without secrets.
+//
+// Against `Groth16Prover` the price is size and speed:
+//
+// - proofs are a few dozen group and field elements rather than three group
+//   elements, and grow logarithmically with the circuit;
+// - verification runs a multi-scalar multiplication over the whole
+//   commitment key, so it is linear in the circuit size where Groth16 is
+//   three pairings;
+// - proving is several times slower, the IPA openings adding to Marlin's
+//   own overhead.
+//
+// Soundness rests on discrete log in G1 and the random oracle model
+// (Fiat-Shamir with Blake2s) instead of pairing knowledge assumptions. This
+// backend is a straightforward wiring of the arkworks crates and has seen no
+// tuning; build it with the `marlin` feature.
+pub struct MarlinIpaProver;
+
+type Pcs = InnerProductArgPC<G1Affine, Blake2s, DensePolynomial<Fr>>;
+type MarlinIpa = Marlin<Fr, Pcs, Blake2s>;
+
+pub type MarlinError = ark_marlin::Error<ark_poly_commit::Error>;
+
+impl DSAProver for MarlinIpaProver {
+    type ProvingKey = IndexProverKey<Fr, Pcs>;
+    type VerifyingKey = IndexVerifierKey<Fr, Pcs>;
+    type Proof = Proof<Fr, Pcs>;
+    type Error = MarlinError;
+
+    // Sizes the commitment key for this circuit, padded as Marlin pads the
+    // matrices, so the keys fit this circuit and any smaller one
+    fn setup<R: RngCore + CryptoRng>(
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), MarlinError> {
+        let matrices = constraint_matrices(circuit)
+            .map_err(|e| MarlinError::AHPError(AHPError::ConstraintSystemError(e)))?;
+        let num_variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        let size = matrices.num_constraints.max(num_variables);
+        let num_non_zero =
+            matrices.a_num_non_zero + matrices.b_num_non_zero + matrices.c_num_non_zero;
+        let srs = MarlinIpa::universal_setup(size, size, num_non_zero, rng)?;
+        MarlinIpa::index(&srs, circuit.clone())
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<Self::Proof, MarlinError> {
+        MarlinIpa::prove(pk, circuit.clone(), rng)
+    }
+
+    // The verifier's RNG draws the weights of the batched IPA opening check,
+    // which a prover must not be able to predict, so it comes from entropy
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Fr],
+        proof: &Self::Proof,
+    ) -> Result<bool, MarlinError> {
+        MarlinIpa::verify(vk, public_inputs, proof, &mut seeded_rng(None))
+    }
+}
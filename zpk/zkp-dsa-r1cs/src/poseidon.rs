@@ -0,0 +1,325 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, FpParameters, PrimeField, Zero};
+use ark_relations::lc;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
+};
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+use crate::circuit::{
+    enforce_signature, to_field, DSAVerificationCircuit, DomainInputs, Input, SignatureInputs,
+};
+use crate::gadgets::{enforce_canonical_bits, enforce_remainder_range};
+
+// Poseidon over the BLS12-381 scalar field as instantiated by the sponge of
+// ark-crypto-primitives 0.4, whose default constraint-optimized parameters
+// for rate 5 are width 6, the x^5 S-box, 8 full and 57 partial rounds.
+// ark-crypto-primitives 0.3 has no such instance, so the constants are
+// generated here the same way, with the Grain LFSR of the Poseidon
+// reference script (generate_parameters_grain.sage), and digests match
+// that sponge's.
+const WIDTH: usize = 6;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+const NUM_ROUNDS: usize = FULL_ROUNDS + PARTIAL_ROUNDS;
+
+// The sponge's first state element is its capacity; message elements are
+// added to the other five
+const CAPACITY: usize = 1;
+const RATE: usize = WIDTH - CAPACITY;
+
+const FIELD_BITS: u32 = <Fr as PrimeField>::Params::MODULUS_BITS;
+
+// The reference script's bit generator, seeded with the instance: 01 for a
+// prime field, 0000 for x^alpha, the field size and width in 12 bits each,
+// the full and partial round counts in 10 bits each, then 30 ones. It is
+// clocked 160 times before the first output.
+struct GrainLfsr {
+    state: [bool; 80],
+    head: usize,
+}
+
+impl GrainLfsr {
+    fn new() -> Self {
+        let mut bits = vec![false, true, false, false, false, false];
+        let fields = [
+            (FIELD_BITS as usize, 12),
+            (WIDTH, 12),
+            (FULL_ROUNDS, 10),
+            (PARTIAL_ROUNDS, 10),
+        ];
+        for (value, len) in fields {
+            bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        bits.resize(80, true);
+        let mut lfsr = GrainLfsr { state: bits.try_into().expect("80 seed bits"), head: 0 };
+        for _ in 0..160 {
+            lfsr.clock();
+        }
+        lfsr
+    }
+
+    fn clock(&mut self) -> bool {
+        let tap = |i: usize| self.state[(self.head + i) % 80];
+        let bit = tap(62) ^ tap(51) ^ tap(38) ^ tap(23) ^ tap(13) ^ tap(0);
+        self.state[self.head] = bit;
+        self.head = (self.head + 1) % 80;
+        bit
+    }
+
+    // Bits are clocked in pairs, and the second of a pair is output only
+    // if the first is 1
+    fn next_bit(&mut self) -> bool {
+        while !self.clock() {
+            self.clock();
+        }
+        self.clock()
+    }
+
+    // FIELD_BITS output bits, most significant first
+    fn next_integer(&mut self) -> BigUint {
+        (0..FIELD_BITS).fold(BigUint::zero(), |acc, _| (acc << 1u32) + u32::from(self.next_bit()))
+    }
+
+    // Round keys are sampled by rejection, discarding integers above the
+    // field modulus
+    fn next_round_key(&mut self) -> Fr {
+        let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+        loop {
+            let value = self.next_integer();
+            if value < modulus {
+                return to_field(&value);
+            }
+        }
+    }
+
+    // The MDS matrix's points are reduced mod the field modulus instead
+    fn next_reduced(&mut self) -> Fr {
+        Fr::from_le_bytes_mod_order(&self.next_integer().to_bytes_le())
+    }
+}
+
+// Round keys, one per state element and round, and the MDS matrix
+#[derive(Debug, Clone)]
+pub struct PoseidonParams {
+    pub round_keys: Vec<[Fr; WIDTH]>,
+    pub mds: [[Fr; WIDTH]; WIDTH],
+}
+
+// The instance's constants, generated on first use. The LFSR yields the
+// round keys and then the points x_i, y_j of the Cauchy matrix
+// 1 / (x_i + y_j). The reference script draws matrices until one passes its
+// invariant subspace checks; ark-crypto-primitives' config for this
+// instance skips none, so this takes the first one drawn as well.
+pub fn poseidon() -> &'static PoseidonParams {
+    static POSEIDON: OnceLock<PoseidonParams> = OnceLock::new();
+    POSEIDON.get_or_init(|| {
+        let mut lfsr = GrainLfsr::new();
+        let round_keys =
+            (0..NUM_ROUNDS).map(|_| [(); WIDTH].map(|_| lfsr.next_round_key())).collect();
+        let xs = [(); WIDTH].map(|_| lfsr.next_reduced());
+        let ys = [(); WIDTH].map(|_| lfsr.next_reduced());
+        let mds = xs.map(|x| ys.map(|y| (x + y).inverse().expect("x_i + y_j is non-zero")));
+        PoseidonParams { round_keys, mds }
+    })
+}
+
+// The first and last four rounds are full, applying the S-box to every
+// element; the partial rounds between apply it to element 0 only
+fn is_full_round(round: usize) -> bool {
+    !(FULL_ROUNDS / 2..FULL_ROUNDS / 2 + PARTIAL_ROUNDS).contains(&round)
+}
+
+// Each round adds its keys, applies x^5 and multiplies by the MDS matrix
+fn permute(state: &mut [Fr; WIDTH]) {
+    let params = poseidon();
+    for (round, keys) in params.round_keys.iter().enumerate() {
+        for (i, (element, key)) in state.iter_mut().zip(keys).enumerate() {
+            *element += key;
+            if is_full_round(round) || i == 0 {
+                *element = element.pow([5u64]);
+            }
+        }
+        let mixed = params.mds.map(|row| {
+            row.iter().zip(state.iter()).fold(Fr::zero(), |acc, (m, element)| acc + *m * element)
+        });
+        *state = mixed;
+    }
+}
+
+// The message length followed by the message, in blocks of up to `RATE`
+// elements. Absorbing the length keeps messages that differ only in
+// trailing zeros apart, since absorbing a zero leaves the state unchanged.
+fn absorbed<T: Clone>(msg: &[T], len: T) -> Vec<Vec<T>> {
+    let elements: Vec<T> = std::iter::once(len).chain(msg.iter().cloned()).collect();
+    elements.chunks(RATE).map(<[T]>::to_vec).collect()
+}
+
+// Hashes the message with the sponge, starting from the zero state: each
+// block is added to the rate elements and followed by a permutation, and
+// the digest is the first rate element. This is `PoseidonSponge` absorbing
+// the length and the message and squeezing one element.
+pub fn poseidon_digest(msg: &[Fr]) -> Fr {
+    let mut state = [Fr::zero(); WIDTH];
+    for block in absorbed(msg, Fr::from(msg.len() as u64)) {
+        for (element, m) in state[CAPACITY..].iter_mut().zip(block) {
+            *element += m;
+        }
+        permute(&mut state);
+    }
+    state[CAPACITY]
+}
+
+// The message hash of a `PoseidonDSACircuit`: the low bitlen(q) bits of the
+// digest, reduced mod q. Taking the low bits mirrors how `hash_to_scalar`
+// truncates SHA-256 to bitlen(q) bits, while avoiding the top bits of a
+// field element, which are biased towards zero.
+pub(crate) fn poseidon_scalar(msg: &[Fr], q: &BigUint) -> BigUint {
+    let digest: BigUint = poseidon_digest(msg).into();
+    let low_bits = digest & ((BigUint::from(1u32) << q.bits()) - 1u32);
+    low_bits % q
+}
+
+// A linear combination together with its value
+type Term = (LinearCombination<Fr>, Fr);
+
+// x^5 in three multiplications
+fn enforce_sbox(cs: &ConstraintSystemRef<Fr>, (x, x_val): Term) -> Result<Term, SynthesisError> {
+    let square = |(a, a_val): &Term, (b, b_val): &Term| -> Result<Term, SynthesisError> {
+        let product_val = *a_val * b_val;
+        let product = cs.new_witness_variable(|| Ok(product_val))?;
+        cs.enforce_constraint(a.clone(), b.clone(), lc!() + product)?;
+        Ok((lc!() + product, product_val))
+    };
+    let x = (x, x_val);
+    let x2 = square(&x, &x)?;
+    let x4 = square(&x2, &x2)?;
+    square(&x4, &x)
+}
+
+// `permute` as constraints: 3 per S-box, so 3 * (8 * WIDTH + 57) = 315
+// per call. Round-key additions and the MDS layer are linear and only grow
+// the combinations.
+fn enforce_permutation(
+    cs: &ConstraintSystemRef<Fr>,
+    mut state: Vec<Term>,
+) -> Result<Vec<Term>, SynthesisError> {
+    let params = poseidon();
+    for (round, keys) in params.round_keys.iter().enumerate() {
+        for (i, ((lc, val), &key)) in state.iter_mut().zip(keys).enumerate() {
+            *lc += (key, Variable::One);
+            *val += key;
+            if is_full_round(round) || i == 0 {
+                let (x5, x5_val) = enforce_sbox(cs, (lc.clone(), *val))?;
+                *lc = x5;
+                *val = x5_val;
+            }
+        }
+        state = params
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter().zip(&state).fold((lc!(), Fr::zero()), |(lc, val), (&m, (s, s_val))| {
+                    (lc + (m, s), val + m * s_val)
+                })
+            })
+            .collect();
+    }
+    Ok(state)
+}
+
+// `poseidon_digest` as constraints over already-allocated message
+// variables, returning a witness holding the digest
+fn enforce_digest(
+    cs: &ConstraintSystemRef<Fr>,
+    msg: &[Input],
+) -> Result<(Variable, Fr), SynthesisError> {
+    let len = (lc!() + (Fr::from(msg.len() as u64), Variable::One), Fr::from(msg.len() as u64));
+    let elements: Vec<Term> = msg.iter().map(|m| (lc!() + m.var, to_field(&m.val))).collect();
+    let mut state: Vec<Term> = vec![(lc!(), Fr::zero()); WIDTH];
+    for block in absorbed(&elements, len) {
+        for ((lc, val), (m, m_val)) in state[CAPACITY..].iter_mut().zip(block) {
+            *lc = &*lc + &m;
+            *val += m_val;
+        }
+        state = enforce_permutation(cs, state)?;
+    }
+    let (acc, acc_val) = state.swap_remove(CAPACITY);
+    let digest = cs.new_witness_variable(|| Ok(acc_val))?;
+    cs.enforce_constraint(acc, lc!() + Variable::One, lc!() + digest)?;
+    Ok((digest, acc_val))
+}
+
+// DSA verification where h_x is not a public input but the Poseidon hash
+// of a public message, computed in the circuit, so a proof also attests to
+// the hashing. The public inputs are the circuit's other six followed by
+// the message elements. The digest gets a canonical bit decomposition, and
+// its low bitlen(q) bits z < 2q reduce to h_x with a boolean quotient, so
+// h_x is fully determined by the message. On top of the plain circuit this
+// costs 315 constraints per five absorbed elements, counting the length
+// ahead of the message, 511 for the digest and its decomposition and
+// 2 bitlen(q) + 5 for the reduction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonDSACircuit {
+    pub circuit: DSAVerificationCircuit<Fr>,
+    pub msg: Vec<Fr>,
+}
+
+impl DSAVerificationCircuit<Fr> {
+    // Replaces h_x with the Poseidon hash of `msg`, which the signature must
+    // have been made over
+    pub fn with_poseidon_message(mut self, msg: Vec<Fr>) -> PoseidonDSACircuit {
+        self.h_x = to_field(&poseidon_scalar(&msg, &self.params.q.into()));
+        PoseidonDSACircuit { circuit: self, msg }
+    }
+}
+
+impl PoseidonDSACircuit {
+    // Public inputs in the order they are allocated in `generate_constraints`
+    pub fn public_inputs(&self) -> Vec<Fr> {
+        let c = &self.circuit;
+        let mut inputs = vec![c.key.0, c.sig.r, c.sig.s, c.params.p, c.params.q, c.params.g];
+        inputs.extend_from_slice(&self.msg);
+        inputs
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PoseidonDSACircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let c = self.circuit;
+        c.check_strict()?;
+        let y = Input::alloc(&cs, c.key.0)?;
+        let r = Input::alloc(&cs, c.sig.r)?;
+        let s = Input::alloc(&cs, c.sig.s)?;
+        let p = Input::alloc(&cs, c.params.p)?;
+        let q = Input::alloc(&cs, c.params.q)?;
+        let g = Input::alloc(&cs, c.params.g)?;
+        let msg = self
+            .msg
+            .iter()
+            .map(|&m| Input::alloc(&cs, m))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (digest, digest_val) = enforce_digest(&cs, &msg)?;
+        let bits = enforce_canonical_bits(&cs, digest)?;
+        // z = low bitlen(q) bits < 2q, so z = q * k + h_x with k in {0, 1}
+        let num_bits = q.val.bits() as usize;
+        let z = bits[..num_bits].iter().enumerate().fold(lc!(), |z, (i, &bit)| {
+            z + (to_field::<Fr>(&(BigUint::from(1u32) << i)), bit)
+        });
+        let digest_val: BigUint = digest_val.into();
+        let z_val = digest_val & ((BigUint::from(1u32) << num_bits) - 1u32);
+        let k_val = Fr::from(u64::from(z_val >= q.val));
+        let h_x_val = &z_val % &q.val;
+        let k = cs.new_witness_variable(|| Ok(k_val))?;
+        cs.enforce_constraint(lc!() + k, lc!() + Variable::One - k, lc!())?;
+        let h_x = Input { var: cs.new_witness_variable(|| Ok(to_field(&h_x_val)))?, val: h_x_val };
+        cs.enforce_constraint(lc!() + q.var, lc!() + k, z - h_x.var)?;
+        enforce_remainder_range(&cs, h_x.var, q.var, &q.val)?;
+
+        let domain = DomainInputs { p: p.into(), q: q.into(), g };
+        let sig = SignatureInputs { y, h_x, r, s };
+        let witness = sig.witness(&domain)?;
+        enforce_signature(&cs, &domain, &sig, &witness)
+    }
+}
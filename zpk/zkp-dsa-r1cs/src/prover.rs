@@ -0,0 +1,409 @@
+use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey};
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Read;
+use std::path::Path;
+use crate::circuit::DSAVerificationCircuit;
+use crate::error::{PqZkpError, VerificationFailure};
+use crate::json::DSAInputs;
+use crate::serialization::{
+    load_verifying_key, proof_from_bytes, read_proof_frame, verifying_key_from_bytes,
+};
+
+// Proving-system interface for the DSA circuit, so calling code doesn't
+// depend on a specific backend. `setup` produces whatever keys the backend
+// needs for this circuit: a circuit-specific setup for Groth16, or a
+// universal SRS followed by indexing for schemes such as Marlin. `verify`
+// takes the public inputs in `DSAVerificationCircuit::public_inputs` order.
+pub trait DSAProver {
+    type ProvingKey;
+    type VerifyingKey;
+    type Proof;
+    type Error: std::fmt::Debug;
+
+    fn setup<R: RngCore + CryptoRng>(
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error>;
+
+    fn prove<R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>;
+
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Fr],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+}
+
+// Runs `f`, recording its duration in the `name` histogram when the
+// `metrics` feature is on. Without it this is just `f()`.
+#[inline(always)]
+fn timed<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "metrics")]
+    {
+        let start = std::time::Instant::now();
+        let result = f();
+        metrics::histogram!(name).record(start.elapsed().as_secs_f64());
+        result
+    }
+    #[cfg(not(feature = "metrics"))]
+    {
+        let _ = name;
+        f()
+    }
+}
+
+// The default backend: Groth16 over BLS12-381. Its `setup` samples the
+// toxic waste (tau, alpha, beta, gamma, delta) in this process and only
+// drops it afterwards, so whoever runs it is able to forge proofs. That is
+// fine for tests and demos but not for production keys, and
+// `rerandomize::setup_with_delta_updates` does not change it.
+pub struct Groth16Prover;
+
+impl DSAProver for Groth16Prover {
+    type ProvingKey = ProvingKey<Bls12_381>;
+    type VerifyingKey = VerifyingKey<Bls12_381>;
+    type Proof = Proof<Bls12_381>;
+    type Error = PqZkpError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), PqZkpError> {
+        timed("pq_zkp.setup_seconds", || {
+            Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), rng)
+        })
+        .map_err(PqZkpError::Setup)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        circuit: &DSAVerificationCircuit,
+        rng: &mut R,
+    ) -> Result<Self::Proof, PqZkpError> {
+        timed("pq_zkp.prove_seconds", || Groth16::<Bls12_381>::prove(pk, circuit.clone(), rng))
+            .map_err(PqZkpError::Proving)
+    }
+
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Fr],
+        proof: &Self::Proof,
+    ) -> Result<bool, PqZkpError> {
+        timed("pq_zkp.verify_seconds", || Groth16::<Bls12_381>::verify(vk, public_inputs, proof))
+            .map_err(PqZkpError::Verification)
+    }
+}
+
+// A fixed seed gives reproducible keys and proofs (tests, CI); `None` seeds
+// from OS entropy, which is what proofs outside of testing should use
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+// Single-party setup with the same caveat as `Groth16Prover::setup`
+pub fn setup_keys(
+    circuit: &DSAVerificationCircuit,
+    seed: Option<u64>,
+) -> Result<(ProvingKey<Bls12_381>, VerifyingKey<Bls12_381>), PqZkpError> {
+    Groth16Prover::setup(circuit, &mut seeded_rng(seed))
+}
+
+pub fn prove_proof(
+    pk: &ProvingKey<Bls12_381>,
+    circuit: &DSAVerificationCircuit,
+    seed: Option<u64>,
+) -> Result<Proof<Bls12_381>, PqZkpError> {
+    Groth16Prover::prove(pk, circuit, &mut seeded_rng(seed))
+}
+
+// The RNG `prove_many` uses for the circuit at `index`: a SHA-256 of the base
+// seed and the index, so every circuit gets an independent stream and a
+// single proof from a batch can be reproduced on its own
+pub fn derive_rng(seed: u64, index: usize) -> StdRng {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update((index as u64).to_le_bytes());
+    StdRng::from_seed(hasher.finalize().into())
+}
+
+// Proves independent circuits under one key, on all cores when the
+// `parallel` feature is on. Each proof depends only on its circuit, `seed`
+// and its position in `circuits`, so the output is the same with or without
+// the feature. The first proving error is returned.
+pub fn prove_many(
+    pk: &ProvingKey<Bls12_381>,
+    circuits: Vec<DSAVerificationCircuit>,
+    seed: u64,
+) -> Result<Vec<Proof<Bls12_381>>, PqZkpError> {
+    let prove = |(index, circuit): (usize, DSAVerificationCircuit)| {
+        Groth16Prover::prove(pk, &circuit, &mut derive_rng(seed, index))
+    };
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        circuits.into_par_iter().enumerate().map(prove).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        circuits.into_iter().enumerate().map(prove).collect()
+    }
+}
+
+// Options for the high-level entry points such as `prove_and_verify`.
+// Start from `ProverConfig::default()` and override fields with struct
+// update syntax, so callers keep compiling as options are added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProverConfig {
+    // Seed for the setup and proving RNG, as in `seeded_rng`: `Some` gives
+    // reproducible keys and proofs, `None` (the default) uses OS entropy
+    pub seed: Option<u64>,
+    // Log every stage of the pipeline with its duration through `log` at
+    // debug level; off by default
+    pub verbose: bool,
+}
+
+impl ProverConfig {
+    // Runs `f` as the pipeline stage `name`, logging its duration if verbose
+    fn stage<T>(&self, name: &str, f: impl FnOnce() -> T) -> T {
+        let start = std::time::Instant::now();
+        let result = f();
+        if self.verbose {
+            debug_log!("{} took {:?}", name, start.elapsed());
+        }
+        result
+    }
+}
+
+// Runs Groth16 setup, proving and verification for a single circuit.
+// Parameters and the invertibility of s are checked first; the public
+// inputs are taken from `DSAVerificationCircuit::public_inputs`.
+pub fn prove_and_verify(
+    circuit: &DSAVerificationCircuit,
+    config: &ProverConfig,
+) -> Result<bool, PqZkpError> {
+    let mut rng = seeded_rng(config.seed);
+    config.stage("parameter checks", || {
+        circuit.validate_params().map_err(PqZkpError::InvalidParams)?;
+        circuit.signature_inverse().map_err(PqZkpError::Circuit)
+    })?;
+    let (pk, vk) = config.stage("setup", || Groth16Prover::setup(circuit, &mut rng))?;
+    let proof = config.stage("proving", || Groth16Prover::prove(&pk, circuit, &mut rng))?;
+    config.stage("verification", || Groth16Prover::verify(&vk, &circuit.public_inputs(), &proof))
+}
+
+// Precomputes the pairing data of `vk`, including e(alpha, beta), so that
+// services verifying many proofs under one key pay for it only once
+pub fn prepare_vk(vk: &VerifyingKey<Bls12_381>) -> PreparedVerifyingKey<Bls12_381> {
+    ark_groth16::prepare_verifying_key(vk)
+}
+
+// `Groth16Prover::verify` against a key prepared with `prepare_vk`
+pub fn verify_prepared(
+    pvk: &PreparedVerifyingKey<Bls12_381>,
+    public_inputs: &[Fr],
+    proof: &Proof<Bls12_381>,
+) -> Result<bool, PqZkpError> {
+    let prepared_inputs = prepare_public_inputs(pvk, public_inputs)?;
+    verify_with_prepared_inputs(pvk, &prepared_inputs, proof)
+}
+
+// The public-input term IC(x) = gamma_abc_0 + sum x_i * gamma_abc_i of the
+// Groth16 equation, an MSM with one base per input. Verifying several proofs
+// of the same statement (e.g. the same message and key) can compute it once
+// and pass it to `verify_with_prepared_inputs`. With the circuit's seven
+// inputs the MSM is small next to the three pairings, so this only pays off
+// when the same inputs are checked repeatedly.
+pub fn prepare_public_inputs(
+    pvk: &PreparedVerifyingKey<Bls12_381>,
+    public_inputs: &[Fr],
+) -> Result<G1Projective, PqZkpError> {
+    check_input_count(&pvk.vk, public_inputs)?;
+    ark_groth16::prepare_inputs(pvk, public_inputs).map_err(PqZkpError::Verification)
+}
+
+// `verify_prepared` with the public inputs already reduced by
+// `prepare_public_inputs` under the same `pvk`
+pub fn verify_with_prepared_inputs(
+    pvk: &PreparedVerifyingKey<Bls12_381>,
+    prepared_inputs: &G1Projective,
+    proof: &Proof<Bls12_381>,
+) -> Result<bool, PqZkpError> {
+    timed("pq_zkp.verify_seconds", || {
+        ark_groth16::verify_proof_with_prepared_inputs(pvk, proof, prepared_inputs)
+    })
+    .map_err(PqZkpError::Verification)
+}
+
+type G1Prepared = <Bls12_381 as PairingEngine>::G1Prepared;
+type G2Prepared = <Bls12_381 as PairingEngine>::G2Prepared;
+
+// Folds the Groth16 equation e(A, B) = e(alpha, beta) e(IC(x), gamma) e(C, delta)
+// of every item with random 128-bit weights into one product of pairings
+fn batch_pairing_check(
+    vk: &VerifyingKey<Bls12_381>,
+    items: &[(Vec<Fr>, Proof<Bls12_381>)],
+    rng: &mut impl RngCore,
+) -> bool {
+    let mut pairs = Vec::with_capacity(items.len() + 3);
+    let mut weight_sum = Fr::zero();
+    let mut ic_sum = G1Projective::zero();
+    let mut c_sum = G1Projective::zero();
+    for (inputs, proof) in items {
+        if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+            return false;
+        }
+        let weight = Fr::from(((rng.next_u64() as u128) << 64) | rng.next_u64() as u128);
+        let mut ic = vk.gamma_abc_g1[0].into_projective();
+        for (input, base) in inputs.iter().zip(&vk.gamma_abc_g1[1..]) {
+            ic += base.mul(input.into_repr());
+        }
+        weight_sum += weight;
+        ic_sum += ic.mul(weight.into_repr());
+        c_sum += proof.c.mul(weight.into_repr());
+        pairs.push((
+            G1Prepared::from(proof.a.mul(weight.into_repr()).into_affine()),
+            G2Prepared::from(proof.b),
+        ));
+    }
+    pairs.push((
+        G1Prepared::from((-vk.alpha_g1.mul(weight_sum.into_repr())).into_affine()),
+        G2Prepared::from(vk.beta_g2),
+    ));
+    pairs.push((G1Prepared::from((-ic_sum).into_affine()), G2Prepared::from(vk.gamma_g2)));
+    pairs.push((G1Prepared::from((-c_sum).into_affine()), G2Prepared::from(vk.delta_g2)));
+    Bls12_381::product_of_pairings(&pairs).is_one()
+}
+
+// Verifies many proofs under one key. A single randomized pairing check
+// covers the whole batch; only if it fails are the items verified one by one
+// to find the invalid ones. Items that cannot be verified count as invalid.
+pub fn verify_batch(
+    vk: &VerifyingKey<Bls12_381>,
+    items: &[(Vec<Fr>, Proof<Bls12_381>)],
+) -> Vec<bool> {
+    if batch_pairing_check(vk, items, &mut seeded_rng(None)) {
+        return vec![true; items.len()];
+    }
+    items
+        .iter()
+        .map(|(inputs, proof)| Groth16Prover::verify(vk, inputs, proof).unwrap_or(false))
+        .collect()
+}
+
+// Number of public inputs `vk` was generated for. `gamma_abc_g1` holds one
+// base per input plus one for the constant term.
+pub fn expected_public_inputs(vk: &VerifyingKey<Bls12_381>) -> usize {
+    vk.gamma_abc_g1.len().saturating_sub(1)
+}
+
+// Verifies a stream of proofs written with `write_proof_frame`, reading one
+// frame per entry of `public_inputs` and holding only that frame in memory.
+// Each item is the result for one proof. A stream that ends early yields an
+// I/O error for the first missing proof and stops; frames beyond the last
+// input are left unread.
+pub fn verify_stream<'a, I, R>(
+    vk: &'a VerifyingKey<Bls12_381>,
+    public_inputs: I,
+    mut reader: R,
+) -> impl Iterator<Item = Result<bool, PqZkpError>> + 'a
+where
+    I: IntoIterator<Item = Vec<Fr>>,
+    I::IntoIter: 'a,
+    R: Read + 'a,
+{
+    let pvk = prepare_vk(vk);
+    let mut ended = false;
+    public_inputs.into_iter().map_while(move |inputs| {
+        if ended {
+            return None;
+        }
+        let result = match read_proof_frame(&mut reader) {
+            Ok(proof) => {
+                check_input_count(vk, &inputs).and_then(|()| verify_prepared(&pvk, &inputs, &proof))
+            }
+            Err(e) => {
+                ended = matches!(e, PqZkpError::Io(_));
+                Err(e)
+            }
+        };
+        Some(result)
+    })
+}
+
+// Fails with `PublicInputCount` unless `vk` is for exactly this many
+// public inputs, which arkworks would only report as a malformed key
+fn check_input_count(vk: &VerifyingKey<Bls12_381>, public_inputs: &[Fr]) -> Result<(), PqZkpError> {
+    let expected = expected_public_inputs(vk);
+    if public_inputs.len() != expected {
+        return Err(PqZkpError::PublicInputCount { expected, found: public_inputs.len() });
+    }
+    Ok(())
+}
+
+// Verifies a proof given entirely in serialized form: the verifying key as
+// written by `save_keys`, the public inputs as `DSAInputs` JSON and the proof
+// from `proof_to_bytes`. Needs no randomness, so it also runs where no
+// entropy source is available.
+pub fn verify_serialized(
+    vk_bytes: &[u8],
+    public_inputs_json: &str,
+    proof_bytes: &[u8],
+) -> Result<bool, PqZkpError> {
+    let vk = verifying_key_from_bytes(vk_bytes)?;
+    let inputs: DSAInputs = serde_json::from_str(public_inputs_json).map_err(std::io::Error::from)?;
+    let circuit = inputs.to_circuit::<Fr>()?;
+    let proof = proof_from_bytes(proof_bytes)?;
+    let public_inputs = circuit.public_inputs();
+    check_input_count(&vk, &public_inputs)?;
+    Groth16Prover::verify(&vk, &public_inputs, &proof)
+}
+
+// The on-disk counterpart of `verify_serialized`: a verifying key written by
+// `save_keys`, a `DSAInputs` JSON file and a proof from `proof_to_bytes`
+pub fn verify_from_files(
+    vk_path: &Path,
+    inputs_json_path: &Path,
+    proof_path: &Path,
+) -> Result<bool, PqZkpError> {
+    let vk = load_verifying_key(vk_path)?;
+    let circuit = DSAVerificationCircuit::<Fr>::from_json(inputs_json_path)?;
+    let proof = proof_from_bytes(&std::fs::read(proof_path)?)?;
+    let public_inputs = circuit.public_inputs();
+    check_input_count(&vk, &public_inputs)?;
+    Groth16Prover::verify(&vk, &public_inputs, &proof)
+}
+
+// Verification that reports why a proof was rejected instead of a bare
+// `false`, for logs where failures need triaging. The input count is checked
+// before the proof is decoded, so a mismatched key is reported as such even
+// when the proof bytes are also bad.
+pub fn verify_or_explain(
+    vk: &VerifyingKey<Bls12_381>,
+    public_inputs: &[Fr],
+    proof_bytes: &[u8],
+) -> Result<(), VerificationFailure> {
+    let expected = expected_public_inputs(vk);
+    if public_inputs.len() != expected {
+        return Err(VerificationFailure::PublicInputCount { expected, found: public_inputs.len() });
+    }
+    let proof = proof_from_bytes(proof_bytes).map_err(VerificationFailure::MalformedProof)?;
+    // With the input count checked, arkworks has no other error to return
+    match Groth16Prover::verify(vk, public_inputs, &proof) {
+        Ok(true) => Ok(()),
+        _ => Err(VerificationFailure::PairingCheckFailed),
+    }
+}
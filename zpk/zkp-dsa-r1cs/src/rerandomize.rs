@@ -0,0 +1,131 @@
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_std::rand::{CryptoRng, RngCore};
+use crate::circuit::DSAVerificationCircuit;
+use crate::error::PqZkpError;
+use crate::prover::{DSAProver, Groth16Prover};
+
+// Re-randomization of delta in an existing Groth16 key pair, the update
+// used by the circuit specific ("phase 2") step of Bowe, Gabizon and Miers:
+// each update multiplies delta by a fresh secret d, so delta_g1 and delta_g2
+// are scaled by d while the H and L queries, which carry 1 / delta, are
+// scaled by 1 / d. The key stays valid for the same circuit.
+//
+// This is not a trusted setup ceremony. tau, alpha and beta come from the
+// single-party setup the keys started from and no update touches them, so
+// whoever ran that setup can still forge proofs. Updating delta only removes
+// the need to trust that party with delta; keys for production have to
+// start from a public powers-of-tau transcript instead, which this crate
+// does not implement.
+
+// One party's secret for a delta update. Generate it on that party's own
+// machine and drop it after `rerandomize_delta`; it is deliberately neither
+// serializable nor `Debug`.
+pub struct DeltaUpdate {
+    delta: Fr,
+}
+
+impl DeltaUpdate {
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        loop {
+            let delta = Fr::rand(rng);
+            if !delta.is_zero() {
+                return DeltaUpdate { delta };
+            }
+        }
+    }
+}
+
+fn scale_g1(points: &[G1Affine], scalar: Fr) -> Vec<G1Affine> {
+    let scalar = scalar.into_repr();
+    let scaled: Vec<G1Projective> = points.iter().map(|p| p.mul(scalar)).collect();
+    G1Projective::batch_normalization_into_affine(&scaled)
+}
+
+// Applies one party's delta update to the current proving key. Each party
+// runs this on the key handed to them by the previous one and passes the
+// result on; `verify_delta_update` lets the next party check the update.
+pub fn rerandomize_delta(
+    pk: &ProvingKey<Bls12_381>,
+    update: &DeltaUpdate,
+) -> ProvingKey<Bls12_381> {
+    let d = update.delta;
+    let d_inv = d.inverse().expect("Contributions are non-zero");
+    let mut next = pk.clone();
+    next.delta_g1 = pk.delta_g1.mul(d.into_repr()).into_affine();
+    next.vk.delta_g2 = pk.vk.delta_g2.mul(d.into_repr()).into_affine();
+    next.h_query = scale_g1(&pk.h_query, d_inv);
+    next.l_query = scale_g1(&pk.l_query, d_inv);
+    next
+}
+
+// Random linear combination of `points`, so a whole query can be checked
+// with a single pairing
+fn fold(points: &[G1Affine], weights: &[Fr]) -> G1Projective {
+    points.iter().zip(weights).map(|(p, w)| p.mul(w.into_repr())).sum()
+}
+
+// e(a, b) == e(c, d)
+fn same_ratio(a: G1Affine, b: G2Affine, c: G1Affine, d: G2Affine) -> bool {
+    Bls12_381::pairing(a, b) == Bls12_381::pairing(c, d)
+}
+
+// Checks that `after` is `before` with one delta update applied: only delta
+// and the H and L queries changed, delta_g1 and delta_g2 moved by the same
+// factor, and H and L moved by its inverse. It cannot tell whether the
+// updating party.s secret was random, only that the update is well formed.
+pub fn verify_delta_update(
+    before: &ProvingKey<Bls12_381>,
+    after: &ProvingKey<Bls12_381>,
+    rng: &mut impl RngCore,
+) -> bool {
+    let unchanged = before.vk.alpha_g1 == after.vk.alpha_g1
+        && before.vk.beta_g2 == after.vk.beta_g2
+        && before.vk.gamma_g2 == after.vk.gamma_g2
+        && before.vk.gamma_abc_g1 == after.vk.gamma_abc_g1
+        && before.beta_g1 == after.beta_g1
+        && before.a_query == after.a_query
+        && before.b_g1_query == after.b_g1_query
+        && before.b_g2_query == after.b_g2_query
+        && before.h_query.len() == after.h_query.len()
+        && before.l_query.len() == after.l_query.len();
+    if !unchanged || after.delta_g1.is_zero() || after.vk.delta_g2.is_zero() {
+        return false;
+    }
+
+    // delta_g1' / delta_g1 == delta_g2' / delta_g2
+    if !same_ratio(after.delta_g1, before.vk.delta_g2, before.delta_g1, after.vk.delta_g2) {
+        return false;
+    }
+
+    // Every H and L element was divided by that same factor
+    let weights: Vec<Fr> = (0..before.h_query.len().max(before.l_query.len()))
+        .map(|_| Fr::rand(rng))
+        .collect();
+    let queries = [(&before.h_query, &after.h_query), (&before.l_query, &after.l_query)];
+    queries.iter().all(|(old, new)| {
+        let old = fold(old, &weights).into_affine();
+        let new = fold(new, &weights).into_affine();
+        same_ratio(new, after.vk.delta_g2, old, before.vk.delta_g2)
+    })
+}
+
+// Runs the single-party `Groth16Prover::setup` and then applies each delta
+// update in turn, for tests and demos; independent parties would each run
+// `rerandomize_delta` on their own machine. Either way the result is only as
+// trustworthy as that first setup for tau, alpha and beta.
+pub fn setup_with_delta_updates<R: RngCore + CryptoRng>(
+    circuit: &DSAVerificationCircuit,
+    updates: &[DeltaUpdate],
+    rng: &mut R,
+) -> Result<(ProvingKey<Bls12_381>, VerifyingKey<Bls12_381>), PqZkpError> {
+    let (mut pk, _) = Groth16Prover::setup(circuit, rng)?;
+    for update in updates {
+        pk = rerandomize_delta(&pk, update);
+    }
+    let vk = pk.vk.clone();
+    Ok((pk, vk))
+}
+
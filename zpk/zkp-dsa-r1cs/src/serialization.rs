@@ -0,0 +1,146 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Proof, ProvingKey, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use crate::error::PqZkpError;
+
+const PK_FILE: &str = "pk.bin";
+const VK_FILE: &str = "vk.bin";
+
+// Every key and proof starts with `MAGIC` followed by a one byte format
+// version. Bump `FORMAT_VERSION` whenever a circuit change makes previously
+// written artifacts incompatible, so loading them fails with
+// `PqZkpError::VersionMismatch` instead of a failed verification. Blobs
+// without the magic predate the header and are reported as version 1.
+const MAGIC: &[u8; 4] = b"PQZK";
+pub const FORMAT_VERSION: u8 = 2;
+const HEADERLESS_VERSION: u8 = 1;
+
+fn write_versioned<T: CanonicalSerialize, W: Write>(value: &T, mut writer: W) -> Result<(), PqZkpError> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[FORMAT_VERSION])?;
+    value.serialize(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Reads a whole artifact: like `public_inputs_from_bytes`, anything left
+// in `reader` after the value is rejected, so each value has one encoding
+fn read_versioned<T: CanonicalDeserialize, R: Read>(mut reader: R) -> Result<T, PqZkpError> {
+    let mut header = [0u8; 5];
+    reader.read_exact(&mut header)?;
+    let found = if &header[..4] == MAGIC { header[4] } else { HEADERLESS_VERSION };
+    if found != FORMAT_VERSION {
+        return Err(PqZkpError::VersionMismatch { expected: FORMAT_VERSION, found });
+    }
+    let value = T::deserialize(&mut reader)?;
+    if reader.read(&mut [0u8; 1])? != 0 {
+        return Err(PqZkpError::Serialization(SerializationError::InvalidData));
+    }
+    Ok(value)
+}
+
+// Writes the keys to `dir/pk.bin` and `dir/vk.bin`, creating `dir` if needed
+pub fn save_keys(
+    pk: &ProvingKey<Bls12_381>,
+    vk: &VerifyingKey<Bls12_381>,
+    dir: &Path,
+) -> Result<(), PqZkpError> {
+    fs::create_dir_all(dir)?;
+    write_versioned(pk, BufWriter::new(File::create(dir.join(PK_FILE))?))?;
+    write_versioned(vk, BufWriter::new(File::create(dir.join(VK_FILE))?))?;
+    Ok(())
+}
+
+// Reads keys previously written by `save_keys`
+pub fn load_keys(
+    dir: &Path,
+) -> Result<(ProvingKey<Bls12_381>, VerifyingKey<Bls12_381>), PqZkpError> {
+    let pk = load_proving_key(&dir.join(PK_FILE))?;
+    let vk = load_verifying_key(&dir.join(VK_FILE))?;
+    Ok((pk, vk))
+}
+
+pub fn load_proving_key(path: &Path) -> Result<ProvingKey<Bls12_381>, PqZkpError> {
+    read_versioned(BufReader::new(File::open(path)?))
+}
+
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey<Bls12_381>, PqZkpError> {
+    read_versioned(BufReader::new(File::open(path)?))
+}
+
+// Encodes a key in the format `save_keys` writes to `vk.bin`
+pub fn verifying_key_to_bytes(vk: &VerifyingKey<Bls12_381>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + vk.serialized_size());
+    write_versioned(vk, &mut bytes).expect("Serializing into a Vec cannot fail");
+    bytes
+}
+
+// Decodes a key in the format `save_keys` writes to `vk.bin`
+pub fn verifying_key_from_bytes(bytes: &[u8]) -> Result<VerifyingKey<Bls12_381>, PqZkpError> {
+    read_versioned(bytes)
+}
+
+// Versioned canonical compressed encoding of a proof
+pub fn proof_to_bytes(proof: &Proof<Bls12_381>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + proof.serialized_size());
+    write_versioned(proof, &mut bytes).expect("Serializing into a Vec cannot fail");
+    bytes
+}
+
+pub fn proof_from_bytes(bytes: &[u8]) -> Result<Proof<Bls12_381>, PqZkpError> {
+    read_versioned(bytes)
+}
+
+// Public inputs exactly as arkworks' `CanonicalSerialize` writes a
+// `Vec<Fr>`: a little-endian u64 count, then each element in 32 bytes. No
+// version header, since the encoding doesn't depend on the circuit format.
+pub fn public_inputs_to_bytes(inputs: &[Fr]) -> Vec<u8> {
+    let inputs = inputs.to_vec();
+    let mut bytes = Vec::with_capacity(inputs.serialized_size());
+    inputs.serialize(&mut bytes).expect("Serializing into a Vec cannot fail");
+    bytes
+}
+
+// Decodes `public_inputs_to_bytes` output. Elements at or above the field
+// modulus and trailing bytes are rejected, so every input vector has exactly
+// one accepted encoding.
+pub fn public_inputs_from_bytes(mut bytes: &[u8]) -> Result<Vec<Fr>, PqZkpError> {
+    let inputs = Vec::<Fr>::deserialize(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(PqZkpError::Serialization(SerializationError::InvalidData));
+    }
+    Ok(inputs)
+}
+
+// Proof streams are a sequence of frames: a little-endian u32 length and
+// then that many bytes of `proof_to_bytes` output. Frames longer than this
+// are rejected before anything is allocated for them.
+pub const MAX_PROOF_FRAME: u32 = 1024;
+
+// Appends one frame to a proof stream read by `read_proof_frame`
+pub fn write_proof_frame<W: Write>(writer: &mut W, proof: &Proof<Bls12_381>) -> Result<(), PqZkpError> {
+    let bytes = proof_to_bytes(proof);
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+// Reads the next frame of a proof stream
+pub fn read_proof_frame<R: Read>(reader: &mut R) -> Result<Proof<Bls12_381>, PqZkpError> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > MAX_PROOF_FRAME {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("proof frame of {} bytes exceeds {}", len, MAX_PROOF_FRAME),
+        )
+        .into());
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    proof_from_bytes(&bytes)
+}
@@ -0,0 +1,38 @@
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_groth16::Proof;
+use num_bigint::BigUint;
+
+// A field element as a 0x-prefixed, zero-padded 32-byte big-endian uint256
+fn uint256<F: PrimeField>(value: &F) -> String {
+    let value: BigUint = (*value).into();
+    format!("\"0x{:064x}\"", value)
+}
+
+// The EVM precompiles encode the point at infinity as (0, 0)
+fn g1(point: &G1Affine) -> String {
+    let (x, y) = if point.infinity { (Fq::from(0u64), Fq::from(0u64)) } else { (point.x, point.y) };
+    format!("[{},{}]", uint256(&x), uint256(&y))
+}
+
+// The pairing precompile (EIP-197) expects each Fq2 coordinate with the
+// imaginary part c1 first
+fn g2(point: &G2Affine) -> String {
+    let (x, y) = if point.infinity { (Fq2::from(0u64), Fq2::from(0u64)) } else { (point.x, point.y) };
+    format!(
+        "[[{},{}],[{},{}]]",
+        uint256(&x.c1),
+        uint256(&x.c0),
+        uint256(&y.c1),
+        uint256(&y.c0)
+    )
+}
+
+// Arguments for `verifyProof(uint[2] a, uint[2][2] b, uint[2] c, uint[n] input)`
+// of a Solidity Groth16 verifier such as the one snarkjs exports, in the
+// same `[a],[b],[c],[inputs]` layout as `snarkjs zkey export soliditycalldata`.
+// `public_inputs` are in `DSAVerificationCircuit::public_inputs` order.
+pub fn proof_to_solidity_calldata(proof: &Proof<Bn254>, public_inputs: &[Fr]) -> String {
+    let inputs: Vec<String> = public_inputs.iter().map(uint256).collect();
+    format!("{},{},{},[{}]", g1(&proof.a), g2(&proof.b), g1(&proof.c), inputs.join(","))
+}
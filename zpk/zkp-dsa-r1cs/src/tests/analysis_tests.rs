@@ -0,0 +1,112 @@
+use crate::{
+    check_satisfied, constraint_matrices, constraint_stats, dry_run_setup, estimate_memory, r1cs_nnz,
+    setup_keys, BatchDSACircuit, Bls12DSACircuit, ConstraintStats, DSAParams, DSASig,
+};
+
+#[test]
+fn test_constraint_stats_baseline() {
+    // 4 reductions x 4 constraints + the final equality = 17, plus range
+    // checks of 7 constraints per 2-bit q remainder and 9 for the 3-bit p one,
+    // 4 per 3-bit quotient mod q, 5 for the 4-bit one mod p and 3 for the
+    // bits of w, gives 67. Each 2-bit exponentiation mod p adds 70 more, and
+    // reducing v and r mod q for the final equality adds 13 each.
+    let stats = constraint_stats(&Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3)).expect("Synthesis failed");
+    assert_eq!(
+        stats,
+        ConstraintStats {
+            num_constraints: 233,
+            num_witness_vars: 201,
+            num_input_vars: 7,
+        }
+    );
+}
+
+#[test]
+fn test_constraint_stats_grow_with_modulus_size() {
+    let small = constraint_stats(&Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3)).unwrap();
+    let large = constraint_stats(&Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)).unwrap();
+    assert!(large.num_constraints > small.num_constraints);
+    assert_eq!(large.num_input_vars, small.num_input_vars);
+}
+
+#[test]
+fn test_check_satisfied() {
+    assert!(check_satisfied(Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2)).unwrap());
+    assert!(check_satisfied(Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)).unwrap());
+    // Same group, but r no longer matches v mod q
+    assert!(!check_satisfied(Bls12DSACircuit::new(572, 789, 759, 52, 2039, 1019, 4)).unwrap());
+}
+
+#[test]
+fn test_check_satisfied_propagates_synthesis_errors() {
+    // s = 2 is not invertible modulo q = 6
+    assert!(check_satisfied(Bls12DSACircuit::new(3, 2, 2, 2, 7, 6, 3)).is_err());
+}
+
+#[test]
+fn test_constraint_matrices_match_stats() {
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let stats = constraint_stats(&circuit).unwrap();
+    let matrices = constraint_matrices(&circuit).expect("Synthesis failed");
+
+    assert_eq!(matrices.num_constraints, stats.num_constraints);
+    assert_eq!(matrices.num_instance_variables, stats.num_input_vars + 1);
+    assert_eq!(matrices.num_witness_variables, stats.num_witness_vars);
+    let num_columns = matrices.num_instance_variables + matrices.num_witness_variables;
+    for (matrix, num_non_zero) in [
+        (&matrices.a, matrices.a_num_non_zero),
+        (&matrices.b, matrices.b_num_non_zero),
+        (&matrices.c, matrices.c_num_non_zero),
+    ] {
+        assert_eq!(matrix.len(), stats.num_constraints);
+        assert_eq!(matrix.iter().map(Vec::len).sum::<usize>(), num_non_zero);
+        assert!(matrix.iter().flatten().all(|&(_, column)| column < num_columns));
+    }
+}
+
+#[test]
+fn test_memory_estimate_grows_with_batch_size() {
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let sig = DSASig { y: 572, h_x: 789, r: 758, s: 52 };
+    let estimates: Vec<_> = [1, 2, 4]
+        .iter()
+        .map(|&n| estimate_memory(BatchDSACircuit::new(params, vec![sig; n])).expect("Synthesis failed"))
+        .collect();
+    for pair in estimates.windows(2) {
+        assert!(pair[1].assignment_bytes > pair[0].assignment_bytes);
+        assert!(pair[1].matrix_bytes > pair[0].matrix_bytes);
+        assert!(pair[1].proving_key_bytes > pair[0].proving_key_bytes);
+        assert!(pair[1].total_bytes() > pair[0].total_bytes());
+    }
+
+    // A single-signature batch has the same shape as the plain circuit
+    let single = estimate_memory(Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)).unwrap();
+    assert_eq!(single, estimates[0]);
+}
+
+#[test]
+fn test_dry_run_setup_matches_stats_and_is_cheaper_than_setup() {
+    use std::time::Instant;
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+
+    let start = Instant::now();
+    let stats = dry_run_setup(&circuit).expect("Dry run failed");
+    let dry_run = start.elapsed();
+    let start = Instant::now();
+    setup_keys(&circuit, Some(0)).expect("Setup failed");
+    let full = start.elapsed();
+
+    assert_eq!(stats, constraint_stats(&circuit).unwrap());
+    assert!(dry_run * 10 < full, "dry run {:?} vs setup {:?}", dry_run, full);
+}
+
+#[test]
+fn test_r1cs_nnz_baseline() {
+    // The same 233-constraint system as `test_constraint_stats_baseline`
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3);
+    let nnz = r1cs_nnz(&circuit).expect("Synthesis failed");
+    assert_eq!(nnz, (360, 360, 107));
+
+    let matrices = constraint_matrices(&circuit).unwrap();
+    assert_eq!(nnz, (matrices.a_num_non_zero, matrices.b_num_non_zero, matrices.c_num_non_zero));
+}
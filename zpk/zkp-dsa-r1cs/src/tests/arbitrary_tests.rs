@@ -0,0 +1,11 @@
+#![cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use crate::Bls12DSACircuit;
+
+#[test]
+fn test_arbitrary_reads_inputs_in_new_order() {
+    let bytes: Vec<u8> = [2u64, 2, 2, 1, 7, 3, 2].iter().flat_map(|x| x.to_le_bytes()).collect();
+    let circuit = Bls12DSACircuit::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+    assert_eq!(circuit, Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2));
+    assert_eq!(Bls12DSACircuit::size_hint(0), (56, Some(56)));
+}
@@ -0,0 +1,92 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use std::panic::{self, AssertUnwindSafe};
+use crate::utils::{dsa_sign_components, modular_exponentiation};
+use crate::{BatchDSACircuit, DSAParams, DSASig};
+
+// Three signatures by x = 123 (y = 572) over p = 2039, q = 1019, g = 4
+fn valid_batch() -> BatchDSACircuit {
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let signatures = vec![
+        DSASig { y: 572, h_x: 789, r: 758, s: 52 },
+        DSASig { y: 572, h_x: 100, r: 360, s: 669 },
+        DSASig { y: 572, h_x: 555, r: 344, s: 578 },
+    ];
+    BatchDSACircuit::new(params, signatures)
+}
+
+fn is_satisfied(circuit: BatchDSACircuit) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn test_batch_of_valid_signatures_verifies() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = valid_batch();
+    assert_eq!(circuit.num_signatures(), 3);
+    assert_eq!(circuit.public_inputs::<Fr>().len(), 3 + 4 * 3);
+    assert!(is_satisfied(circuit.clone()));
+
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &circuit.public_inputs(), &proof)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+#[test]
+fn test_batch_with_one_invalid_signature_fails() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let mut tampered = valid_batch();
+    tampered.signatures[1].s = 670;
+    assert!(!is_satisfied(tampered.clone()));
+
+    // As in `negative_tests`, debug builds of ark-groth16 panic on an
+    // unsatisfiable witness; otherwise the proof must be rejected
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(valid_batch(), &mut rng)
+        .expect("Setup failed");
+    let proving = panic::catch_unwind(AssertUnwindSafe(|| {
+        Groth16::<Bls12_381>::prove(&pk, tampered.clone(), &mut rng)
+    }));
+    if let Ok(proof) = proving {
+        let proof = proof.expect("Proving failed");
+        let is_valid = Groth16::<Bls12_381>::verify(&vk, &tampered.public_inputs(), &proof)
+            .expect("Verification failed");
+        assert!(!is_valid, "One bad signature must invalidate the whole batch");
+    }
+}
+
+#[test]
+fn test_batch_with_shared_y_verifies() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = valid_batch().with_shared_y(true);
+    assert_eq!(circuit.public_inputs::<Fr>().len(), 3 + 1 + 3 * 3);
+    assert!(is_satisfied(circuit.clone()));
+
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &circuit.public_inputs(), &proof)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+#[test]
+fn test_shared_y_rejects_signature_under_other_key() {
+    let DSAParams { p, q, g } = valid_batch().params;
+    // x = 5 instead of 123
+    let other_y = modular_exponentiation(g, 5, p);
+    let (r, s) = dsa_sign_components(7, 5, 100, p, q, g).expect("k = 7 gives a signature");
+    let mut batch = valid_batch();
+    batch.signatures[1] = DSASig { y: other_y, h_x: 100, r, s };
+    assert!(is_satisfied(batch.clone()));
+    assert!(!is_satisfied(batch.with_shared_y(true)));
+}
@@ -1,36 +1,517 @@
 use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{BigInteger64, FftParameters, Fp64, Fp64Parameters, FpParameters};
 use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
 use ark_snark::SNARK;
 use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
-use crate::circuit::DSAVerificationCircuit;
+use num_bigint::BigUint;
+use std::str::FromStr;
+use crate::{
+    check_satisfied, prove_and_verify, prove_proof, setup_keys, Bls12DSACircuit, CircuitError,
+    DSAParams, DSAVerificationCircuit, ParamError, PqZkpError, ProverConfig, PublicKey, Signature,
+};
 
 #[test]
 fn test_dsa_verification() {
     let mut rng = StdRng::seed_from_u64(0u64);
-    let circuit = DSAVerificationCircuit {
-        y: Fr::from(3u64),
-        h_x: Fr::from(2u64),
-        r: Fr::from(2u64),
-        s: Fr::from(2u64),
-        p: Fr::from(7u64),
-        q: Fr::from(3u64),
-        g: Fr::from(3u64),
-    };
+    let circuit = DSAVerificationCircuit::from_field_parts(
+        DSAParams { p: Fr::from(7u64), q: Fr::from(3u64), g: Fr::from(3u64) },
+        PublicKey(Fr::from(3u64)),
+        Fr::from(2u64),
+        Signature { r: Fr::from(2u64), s: Fr::from(2u64) },
+    );
     let pk_vk = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
         .expect("Setup failed");
     let (pk, vk) = pk_vk;
     let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
         .expect("Proving failed");
-    let public_inputs = vec![
-        circuit.y,
-        circuit.h_x,
-        circuit.r,
-        circuit.s,
-        circuit.p,
-        circuit.q,
-        circuit.g,
-    ];
+    let public_inputs = circuit.public_inputs();
     let is_valid = Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof)
         .expect("Verification failed");
     assert!(is_valid, "Proof verification should succeed");
 }
+
+#[test]
+fn test_prove_and_verify() {
+    // g = 2 has order 3 mod 7; y = g^1 and (r, s) signs h = 2 with k = 1
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let config = ProverConfig { seed: Some(0), verbose: true };
+    let is_valid = prove_and_verify(&circuit, &config).expect("Pipeline failed");
+    assert!(is_valid, "Proof verification should succeed");
+}
+
+#[test]
+fn test_public_inputs_match_allocated_inputs() {
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3);
+    let public_inputs = circuit.public_inputs();
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    // The instance assignment also contains the constant `one` variable
+    assert_eq!(public_inputs.len(), cs.num_instance_variables() - 1);
+}
+
+#[test]
+fn test_multi_limb_parameters_are_satisfied() {
+    // p = 2^107 - 1 and q = 2^89 - 1 both exceed a single u64 limb. With y = 1
+    // the check reduces to r == (g^u1 mod p) mod q, which fixes r below.
+    let circuit = DSAVerificationCircuit::from_field_parts(
+        DSAParams {
+            p: Fr::from_str("162259276829213363391578010288127").unwrap(),
+            q: Fr::from_str("618970019642690137449562111").unwrap(),
+            g: Fr::from(3u64),
+        },
+        PublicKey(Fr::from(1u64)),
+        Fr::from(5u64),
+        Signature { r: Fr::from_str("249695651675872718599302816").unwrap(), s: Fr::from(7u64) },
+    );
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap(), "Multi-limb parameters should satisfy the circuit");
+}
+
+#[test]
+fn test_modulus_too_wide_for_field_is_rejected() {
+    // 3 | 2^127 - 2, but products mod a 127-bit p can reach the 255-bit field
+    let mut circuit = Bls12DSACircuit::new(1, 1, 1, 1, 1, 3, 1);
+    circuit.params.p = Fr::from_str("170141183460469231731687303715884105727").unwrap();
+    assert_eq!(circuit.validate_params(), Err(ParamError::ModulusTooWide));
+}
+
+#[test]
+fn test_display() {
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3);
+    assert_eq!(circuit.to_string(), "DSA(p=7, q=3, g=3, y=3, r=2, s=2, h=2)");
+    let mut wide = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    wide.params.p = Fr::from_str("170141183460469231731687303715884105727").unwrap();
+    assert_eq!(
+        format!("{}", wide),
+        "DSA(p=170141183460469231731687303715884105727, q=1019, g=4, y=572, r=758, s=52, h=789)"
+    );
+}
+
+#[test]
+fn test_validate_params() {
+    assert_eq!(Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2).validate_params(), Ok(()));
+    assert_eq!(
+        Bls12DSACircuit::new(2, 2, 2, 1, 7, 4, 2).validate_params(),
+        Err(ParamError::QDoesNotDividePMinus1)
+    );
+    // The built-in example: g = 3 has order 6 mod 7, not 3
+    assert_eq!(
+        Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3).validate_params(),
+        Err(ParamError::GeneratorWrongOrder)
+    );
+    assert_eq!(
+        Bls12DSACircuit::new(2, 2, 3, 1, 7, 3, 2).validate_params(),
+        Err(ParamError::SignatureOutOfRange)
+    );
+    assert_eq!(
+        Bls12DSACircuit::new(2, 2, 2, 0, 7, 3, 2).validate_params(),
+        Err(ParamError::SignatureComponentZero("s"))
+    );
+}
+
+#[test]
+fn test_zero_signature_components_are_rejected() {
+    // Zero is checked before the upper bound, so r = 0 with s = q still
+    // reports the zero
+    for (r, s, name) in [(0u64, 1u64, "r"), (1, 0, "s"), (0, 0, "r"), (0, 3, "r")] {
+        let circuit = Bls12DSACircuit::new(2, 2, r, s, 7, 3, 2);
+        assert_eq!(circuit.validate_params(), Err(ParamError::SignatureComponentZero(name)));
+        assert!(matches!(
+            prove_and_verify(&circuit, &ProverConfig::default()),
+            Err(PqZkpError::InvalidParams(ParamError::SignatureComponentZero(n))) if n == name
+        ));
+    }
+}
+
+#[test]
+fn test_prove_and_verify_rejects_invalid_params() {
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3);
+    let result = prove_and_verify(&circuit, &ProverConfig::default());
+    assert!(matches!(
+        result,
+        Err(PqZkpError::InvalidParams(ParamError::GeneratorWrongOrder))
+    ));
+}
+
+#[test]
+fn test_new_matches_field_construction() {
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3);
+    let expected: Vec<Fr> = [3u64, 2, 2, 2, 7, 3, 3].iter().map(|&v| Fr::from(v)).collect();
+    assert_eq!(circuit.public_inputs(), expected);
+}
+
+#[test]
+fn test_compute_witness_values() {
+    // Signature under x = 123 in the p = 2039, q = 1019 group
+    let witness = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)
+        .compute_witness()
+        .expect("s is invertible");
+    let expected = [
+        (&witness.w, 98u64), // 52 * 98 = 5 * 1019 + 1
+        (&witness.u1, 789 * 98 % 1019),
+        (&witness.u2, 758 * 98 % 1019),
+    ];
+    for (value, expected) in expected {
+        assert_eq!(*value, BigUint::from(expected));
+    }
+    assert_eq!(witness.g_u1, BigUint::from(4u32).modpow(&witness.u1, &BigUint::from(2039u32)));
+    assert_eq!(witness.y_u2, BigUint::from(572u32).modpow(&witness.u2, &BigUint::from(2039u32)));
+    assert_eq!(witness.v, &witness.g_u1 * &witness.y_u2 % 2039u32);
+    assert_eq!(witness.v_mod_q, BigUint::from(758u32));
+    assert_eq!(witness.r_mod_q, BigUint::from(758u32));
+
+    // The witnesses synthesis allocates first are exactly these values
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)
+        .generate_constraints(cs.clone())
+        .expect("Synthesis failed");
+    let allocated = cs.borrow().unwrap().witness_assignment[..6].to_vec();
+    let computed: Vec<Fr> = [
+        &witness.w,
+        &witness.u1,
+        &witness.u2,
+        &witness.v,
+        &witness.v_mod_q,
+        &witness.r_mod_q,
+    ]
+    .iter()
+    .map(|v| Fr::from((*v).clone()))
+    .collect();
+    assert_eq!(allocated, computed);
+
+    assert_eq!(
+        Bls12DSACircuit::new(2, 2, 2, 3, 7, 3, 2).compute_witness(),
+        Err(CircuitError::NonInvertibleSignature)
+    );
+}
+
+#[test]
+fn test_modulus_below_two_is_an_error() {
+    use crate::{check_satisfied, setup_keys};
+    for p in [0, 1] {
+        let circuit = Bls12DSACircuit::new(2, 2, 2, 1, p, 3, 2);
+        assert_eq!(circuit.compute_witness(), Err(CircuitError::ModulusTooSmall));
+        assert!(check_satisfied(circuit.clone()).is_err());
+        assert!(setup_keys(&circuit, Some(0)).is_err());
+    }
+}
+
+#[test]
+fn test_witness_uses_every_limb_of_wide_inputs() {
+    // q = 2^89 - 1 is prime and s sits above 2^64, so truncating either to
+    // its low limb would give a different inverse
+    let q = (BigUint::from(1u32) << 89u32) - 1u32;
+    let s = (BigUint::from(1u32) << 70u32) + 12345u32;
+    let mut circuit = Bls12DSACircuit::new(1, 1, 1, 1, 1, 1, 1);
+    circuit.params.q = Fr::from(q.clone());
+    circuit.sig.s = Fr::from(s.clone());
+    let w = circuit.signature_inverse().expect("s is invertible mod a prime q");
+    assert_eq!(&w * &s % &q, BigUint::from(1u32));
+}
+
+#[test]
+fn test_non_invertible_signature_is_reported() {
+    // q = 6 divides p - 1 and g = 3 has order 6, but s = 2 shares a factor with q
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 6, 3);
+    assert_eq!(circuit.signature_inverse(), Err(CircuitError::NonInvertibleSignature));
+    // The composite q is caught before the inverse is attempted
+    let result = prove_and_verify(&circuit, &ProverConfig::default());
+    assert!(matches!(result, Err(PqZkpError::InvalidParams(ParamError::QNotPrime))));
+}
+
+#[test]
+fn test_composite_q_is_rejected() {
+    // 15 | 30 = p - 1 and 2^15 = 1 mod 31, so only primality fails
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 31, 15, 2);
+    assert_eq!(circuit.validate_params(), Err(ParamError::QNotPrime));
+    assert_eq!(Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2).validate_params(), Ok(()));
+}
+
+#[test]
+fn test_non_invertible_signature_fails_proving_cleanly() {
+    use ark_relations::r1cs::SynthesisError;
+    use std::panic::{self, AssertUnwindSafe};
+    // Same domain as above, so the keys from s = 1 fit the s = 2 circuit
+    let (pk, _) = setup_keys(&Bls12DSACircuit::new(3, 2, 2, 1, 7, 6, 3), Some(0)).expect("Setup failed");
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 7, 6, 3);
+
+    // Skipping the `prove_and_verify` pre-checks must still give an error
+    // from synthesis, never a panic or a proof
+    let proving = panic::catch_unwind(AssertUnwindSafe(|| prove_proof(&pk, &circuit, Some(0))));
+    assert!(matches!(
+        proving.expect("Proving must not panic"),
+        Err(PqZkpError::Proving(SynthesisError::AssignmentMissing))
+    ));
+    assert!(matches!(check_satisfied(circuit), Err(SynthesisError::AssignmentMissing)));
+}
+
+#[test]
+fn test_prove_and_verify_over_bn254() {
+    use ark_bn254::{Bn254, Fr as BnFr};
+
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = DSAVerificationCircuit::<BnFr>::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let is_valid = Groth16::<Bn254>::verify(&vk, &circuit.public_inputs(), &proof)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+#[test]
+fn test_from_message_hashes_and_reduces() {
+    // Expected values: leftmost bitlen(q) bits of SHA-256(msg), mod q
+    let cases: [(&[u8], u64, u64); 4] = [
+        (b"abc", 1019, 745),
+        (b"", 1019, 910),
+        (b"abc", 3, 2),
+        // The truncated digest 0b101 equals q and reduces to 0
+        (b"abc", 5, 0),
+    ];
+    for (msg, q, expected) in cases {
+        let circuit = Bls12DSACircuit::from_message(msg, 2, 2, 1, 7, q, 2).unwrap();
+        assert_eq!(circuit.h_x, Fr::from(expected), "msg={:?}, q={}", msg, q);
+    }
+}
+
+#[test]
+fn test_from_message_rejects_zero_q() {
+    let result = Bls12DSACircuit::from_message(b"abc", 2, 2, 1, 7, 0, 2);
+    assert_eq!(result, Err(ParamError::QNotPrime));
+}
+
+#[test]
+fn test_strict_checks_accept_valid_signature() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2).with_strict_checks(true);
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_strict_checks_reject_invalid_signature() {
+    // A valid p = 2039 signature with s bumped from 52 to 53
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 53, 2039, 1019, 4).with_strict_checks(true);
+    match circuit.compute_witness() {
+        Err(CircuitError::SignatureMismatch { r_mod_q, .. }) => {
+            assert_eq!(r_mod_q, BigUint::from(758u32))
+        }
+        other => panic!("expected a signature mismatch, got {:?}", other),
+    }
+    // The check fails synthesis before any input is allocated
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let result = circuit.generate_constraints(cs.clone());
+    assert!(matches!(result, Err(SynthesisError::Unsatisfiable)));
+    assert_eq!(cs.num_instance_variables(), 1);
+    assert_eq!(cs.num_constraints(), 0);
+}
+
+#[test]
+fn test_strict_checks_reject_before_fixed_domain_synthesis() {
+    let circuit = Bls12DSACircuit::with_fixed_domain(2039, 1019, 4)
+        .with_signature(PublicKey(572), 789, &Signature { r: 758, s: 53 })
+        .with_strict_checks(true);
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let result = circuit.generate_constraints(cs.clone());
+    assert!(matches!(result, Err(SynthesisError::Unsatisfiable)));
+    assert_eq!(cs.num_instance_variables(), 1);
+}
+
+// The Mersenne prime field 2^61 - 1, small enough for u64 inputs to overflow
+struct M61Parameters;
+type M61 = Fp64<M61Parameters>;
+
+impl Fp64Parameters for M61Parameters {}
+
+impl FftParameters for M61Parameters {
+    type BigInt = BigInteger64;
+    const TWO_ADICITY: u32 = 1;
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger64 = BigInteger64([0x1fff_ffff_ffff_fff7]);
+}
+
+// Montgomery constants for R = 2^64; 37 generates the multiplicative group
+impl FpParameters for M61Parameters {
+    const MODULUS: BigInteger64 = BigInteger64([M61_MODULUS]);
+    const MODULUS_BITS: u32 = 61;
+    const REPR_SHAVE_BITS: u32 = 3;
+    const R: BigInteger64 = BigInteger64([0x8]);
+    const R2: BigInteger64 = BigInteger64([0x40]);
+    const INV: u64 = 0x2000_0000_0000_0001;
+    const GENERATOR: BigInteger64 = BigInteger64([0x128]);
+    const CAPACITY: u32 = 60;
+    const T: BigInteger64 = BigInteger64([0x0fff_ffff_ffff_ffff]);
+    const T_MINUS_ONE_DIV_TWO: BigInteger64 = BigInteger64([0x07ff_ffff_ffff_ffff]);
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger64 = BigInteger64([0x0fff_ffff_ffff_ffff]);
+}
+
+const M61_MODULUS: u64 = (1 << 61) - 1;
+
+#[test]
+fn test_try_new_rejects_values_outside_the_field() {
+    let circuit = DSAVerificationCircuit::<M61>::try_new(2, 2, 2, 1, 7, 3, M61_MODULUS - 1)
+        .expect("Values below the modulus fit");
+    assert_eq!(circuit.params.g, M61::from(M61_MODULUS - 1));
+
+    for g in [M61_MODULUS, M61_MODULUS + 1, u64::MAX] {
+        assert_eq!(
+            DSAVerificationCircuit::<M61>::try_new(2, 2, 2, 1, 7, 3, g).err(),
+            Some(ParamError::FieldOverflow("g"))
+        );
+    }
+    assert_eq!(
+        DSAVerificationCircuit::<M61>::try_new(2, M61_MODULUS, 2, 1, 7, 3, 2).err(),
+        Some(ParamError::FieldOverflow("h_x"))
+    );
+    // `new` reduces the same value to 0
+    let reduced = DSAVerificationCircuit::<M61>::new(2, 2, 2, 1, 7, 3, M61_MODULUS);
+    assert_eq!(reduced.params.g, M61::from(0u64));
+    assert!(Bls12DSACircuit::try_new(u64::MAX, 2, 2, 1, 7, 3, 2).is_ok());
+}
+
+#[test]
+fn test_circuit_equality() {
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    assert_eq!(circuit, circuit.clone());
+    assert_ne!(circuit, Bls12DSACircuit::new(572, 789, 758, 53, 2039, 1019, 4));
+    // Strict mode is not part of the statement
+    assert_eq!(circuit, circuit.clone().with_strict_checks(true));
+}
+
+#[test]
+fn test_reduction_blocks_satisfy_division_identity() {
+    // The four reductions of DSA verification: w * s, h_x * w and r * w
+    // mod q, then g_u1 * y_u2 mod p. Each must be dividend = modulus *
+    // quotient + remainder with remainder < modulus, and land on the value
+    // the circuit allocates for that step.
+    let cases = [
+        (572u64, 789u64, 758u64, 52u64, 2039u64, 1019u64, 4u64),
+        (572, 100, 360, 669, 2039, 1019, 4),
+        (2, 2, 2, 1, 7, 3, 2),
+    ];
+    for (y, h_x, r, s, p, q, g) in cases {
+        let circuit = Bls12DSACircuit::new(y, h_x, r, s, p, q, g);
+        let witness = circuit.compute_witness().expect("Valid signature");
+        let (h_x, r, s, p, q) = (
+            BigUint::from(h_x),
+            BigUint::from(r),
+            BigUint::from(s),
+            BigUint::from(p),
+            BigUint::from(q),
+        );
+        let blocks = [
+            ("w * s", &witness.w * &s, &q, BigUint::from(1u32)),
+            ("h_x * w", &h_x * &witness.w, &q, witness.u1.clone()),
+            ("r * w", &r * &witness.w, &q, witness.u2.clone()),
+            ("g_u1 * y_u2", &witness.g_u1 * &witness.y_u2, &p, witness.v.clone()),
+        ];
+        for (name, dividend, modulus, expected) in blocks {
+            let quotient = &dividend / modulus;
+            let remainder = &dividend % modulus;
+            assert_eq!(modulus * &quotient + &remainder, dividend, "{}: division identity", name);
+            assert!(&remainder < modulus, "{}: remainder must be below the modulus", name);
+            assert_eq!(remainder, expected, "{}: remainder must match the witness", name);
+        }
+    }
+}
+
+#[test]
+fn test_fixed_domain_circuit() {
+    use crate::{constraint_stats, expected_public_inputs};
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let domain = Bls12DSACircuit::with_fixed_domain(2039, 1019, 4);
+    let fixed = domain.clone().with_signature(PublicKey(572), 789, &Signature { r: 758, s: 52 });
+    assert_eq!(fixed.public_inputs(), circuit.public_inputs()[..4].to_vec());
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    fixed.clone().generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+    let flat = constraint_stats(&circuit).unwrap();
+    assert_eq!(cs.num_instance_variables() - 1, 4);
+    assert!(cs.num_constraints() < flat.num_constraints);
+
+    // Setup needs only the domain
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(domain.clone(), &mut rng)
+        .expect("Setup failed");
+    assert_eq!(expected_public_inputs(&vk), 4);
+    let proof = Groth16::<Bls12_381>::prove(&pk, fixed.clone(), &mut rng).expect("Proving failed");
+    assert!(Groth16::<Bls12_381>::verify(&vk, &fixed.public_inputs(), &proof).unwrap());
+    let mut wrong = fixed.public_inputs();
+    wrong[1] += Fr::from(1u64);
+    assert!(!Groth16::<Bls12_381>::verify(&vk, &wrong, &proof).unwrap());
+
+    // A second signature in the same domain proves under the same keys
+    let other = domain.with_signature(PublicKey(572), 100, &Signature { r: 360, s: 669 });
+    let proof = Groth16::<Bls12_381>::prove(&pk, other.clone(), &mut rng).expect("Proving failed");
+    assert!(Groth16::<Bls12_381>::verify(&vk, &other.public_inputs(), &proof).unwrap());
+}
+
+#[test]
+fn test_fixed_domain_keys_reject_other_domains() {
+    use crate::utils::hash_to_scalar;
+    use crate::{keygen, sign};
+    use sha2::{Digest, Sha256};
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let domain = Bls12DSACircuit::with_fixed_domain(2039, 1019, 4);
+    let (_, vk) =
+        Groth16::<Bls12_381>::circuit_specific_setup(domain, &mut rng).expect("Setup failed");
+
+    // g = 16 also has order q mod p, so this is a valid signature with the
+    // same public input layout, but in a domain the keys above don't know
+    let params = DSAParams { p: 2039, q: 1019, g: 16 };
+    let keys = keygen(&params, &mut rng);
+    let sig = sign(b"other domain", &keys, &params, &mut rng).expect("Signing failed");
+    let h_x = hash_to_scalar(&Sha256::digest(b"other domain"), params.q);
+    let other = Bls12DSACircuit::with_fixed_domain(params.p, params.q, params.g)
+        .with_signature(PublicKey(keys.y), h_x, &sig);
+    let (other_pk, other_vk) =
+        Groth16::<Bls12_381>::circuit_specific_setup(other.clone(), &mut rng).expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&other_pk, other.clone(), &mut rng).expect("Proving failed");
+    assert!(Groth16::<Bls12_381>::verify(&other_vk, &other.public_inputs(), &proof).unwrap());
+    assert!(!Groth16::<Bls12_381>::verify(&vk, &other.public_inputs(), &proof).unwrap());
+}
+
+#[test]
+fn test_builder_matches_new() {
+    let circuit = Bls12DSACircuit::builder().y(3).h_x(2).r(2).s(2).p(7).q(3).g(3).build();
+    assert_eq!(circuit, Ok(Bls12DSACircuit::new(3, 2, 2, 2, 7, 3, 3)));
+}
+
+#[test]
+fn test_builder_reports_missing_field() {
+    let result = Bls12DSACircuit::builder().y(3).h_x(2).r(2).p(7).q(3).g(3).build();
+    assert_eq!(result, Err(CircuitError::MissingField("s")));
+    assert_eq!(Bls12DSACircuit::builder().build(), Err(CircuitError::MissingField("y")));
+
+    let build = || -> Result<Bls12DSACircuit, PqZkpError> {
+        Ok(Bls12DSACircuit::builder().y(3).h_x(2).r(2).s(2).p(7).q(3).build()?)
+    };
+    assert!(matches!(build(), Err(PqZkpError::Circuit(CircuitError::MissingField("g")))));
+}
+
+#[test]
+fn test_builder_reports_each_missing_field() {
+    use crate::DSAVerificationCircuitBuilder;
+    type Setter = fn(DSAVerificationCircuitBuilder<Fr>) -> DSAVerificationCircuitBuilder<Fr>;
+    let setters: [(&str, Setter); 7] = [
+        ("y", |b| b.y(3)),
+        ("h_x", |b| b.h_x(2)),
+        ("r", |b| b.r(2)),
+        ("s", |b| b.s(2)),
+        ("p", |b| b.p(7)),
+        ("q", |b| b.q(3)),
+        ("g", |b| b.g(3)),
+    ];
+    // Every input but one set, in both call orders
+    for (skipped, &(name, _)) in setters.iter().enumerate() {
+        let others = setters.iter().enumerate().filter(|&(i, _)| i != skipped);
+        let forward = others.clone().fold(Bls12DSACircuit::builder(), |b, (_, (_, set))| set(b));
+        let backward = others.rev().fold(Bls12DSACircuit::builder(), |b, (_, (_, set))| set(b));
+        assert_eq!(forward.build(), Err(CircuitError::MissingField(name)));
+        assert_eq!(backward.build(), Err(CircuitError::MissingField(name)));
+    }
+}
@@ -0,0 +1,53 @@
+// Only built with `--features debug-constraints`
+#![cfg(feature = "debug-constraints")]
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::sync::{Mutex, Once};
+use crate::{check_satisfied, Bls12DSACircuit};
+
+// Keeps every message so the tests can look for the synthesis report. The
+// logger is process-wide, so other tests may add messages of their own.
+struct CapturingLogger;
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static LOGGER: CapturingLogger = CapturingLogger;
+static INIT: Once = Once::new();
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        MESSAGES.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+fn captured() -> Vec<String> {
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).expect("No other logger is installed in tests");
+        log::set_max_level(LevelFilter::Debug);
+    });
+    MESSAGES.lock().unwrap().clone()
+}
+
+#[test]
+fn test_failing_step_is_logged() {
+    captured();
+    // s tampered from 52 to 53 in the p = 2039, q = 1019 example, so the
+    // final comparison is the only unsatisfied step
+    assert!(!check_satisfied(Bls12DSACircuit::new(572, 789, 758, 53, 2039, 1019, 4)).unwrap());
+    assert!(
+        captured().iter().any(|m| m.contains("in step `v mod q == r mod q`")),
+        "no report for the failing comparison"
+    );
+}
+
+#[test]
+fn test_satisfied_system_is_logged() {
+    captured();
+    assert!(check_satisfied(Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)).unwrap());
+    assert!(captured().iter().any(|m| m.starts_with("all ") && m.ends_with(" constraints are satisfied")));
+}
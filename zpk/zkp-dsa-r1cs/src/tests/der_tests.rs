@@ -0,0 +1,65 @@
+use ark_bls12_381::Fr;
+use ark_ff::{FpParameters, PrimeField};
+use num_bigint::BigUint;
+use crate::{check_satisfied, parse_der_signature, Bls12DSACircuit, DSAParams, DerError, ParamError};
+
+// SEQUENCE { INTEGER 758, INTEGER 52 }
+const SIGNATURE: [u8; 9] = [0x30, 0x07, 0x02, 0x02, 0x02, 0xf6, 0x02, 0x01, 0x34];
+
+#[test]
+fn test_parse_der_signature() {
+    let (r, s) = parse_der_signature(&SIGNATURE).expect("Parsing failed");
+    assert_eq!((r, s), (BigUint::from(758u32), BigUint::from(52u32)));
+}
+
+#[test]
+fn test_from_der_signature_verifies() {
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let circuit = Bls12DSACircuit::from_der_signature(&SIGNATURE, &params, 572, 789)
+        .expect("Parsing failed");
+    assert_eq!(circuit.sig.r, Fr::from(758u64));
+    assert_eq!(circuit.sig.s, Fr::from(52u64));
+    assert!(check_satisfied(circuit).unwrap());
+}
+
+#[test]
+fn test_parse_der_signature_with_sign_padding_and_long_length() {
+    // r = 0x80 needs a leading zero; the 0x81 length form is only canonical
+    // for lengths of 128 and above, so build an s that long
+    let mut der = vec![0x30, 0x81, 0x88, 0x02, 0x02, 0x00, 0x80, 0x02, 0x81, 0x81, 0x01];
+    der.extend([0u8; 128]);
+    let (r, s) = parse_der_signature(&der).expect("Parsing failed");
+    assert_eq!(r, BigUint::from(0x80u32));
+    assert_eq!(s, BigUint::from(1u32) << 1024);
+}
+
+#[test]
+fn test_parse_der_signature_rejects_malformed_input() {
+    let cases: [(&[u8], DerError); 8] = [
+        (&[], DerError::Truncated),
+        (&SIGNATURE[..5], DerError::Truncated),
+        (&[0x31, 0x00], DerError::UnexpectedTag { expected: 0x30, found: 0x31 }),
+        (&[0x30, 0x80, 0x00, 0x00], DerError::NonCanonicalLength),
+        (&[0x30, 0x81, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01], DerError::NonCanonicalLength),
+        (&[0x30, 0x06, 0x02, 0x01, 0x81, 0x02, 0x01, 0x01], DerError::NegativeInteger),
+        (&[0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01], DerError::NonCanonicalInteger),
+        (&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00], DerError::TrailingData),
+    ];
+    for (der, expected) in cases {
+        assert_eq!(parse_der_signature(der), Err(expected), "input {:02x?}", der);
+    }
+}
+
+#[test]
+fn test_from_der_signature_rejects_r_above_field_modulus() {
+    // r = |Fr| would reduce to 0 in the circuit; its top byte 0x73 needs no
+    // sign padding, so the encoding is a plain 32-byte integer
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let r = modulus.to_bytes_be();
+    let mut der = vec![0x30, r.len() as u8 + 5, 0x02, r.len() as u8];
+    der.extend(&r);
+    der.extend([0x02, 0x01, 0x34]);
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let result = Bls12DSACircuit::from_der_signature(&der, &params, 572, 789);
+    assert_eq!(result.err(), Some(DerError::InvalidParams(ParamError::FieldOverflow("r"))));
+}
@@ -0,0 +1,82 @@
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+use crate::utils::{dsa_verify_native, hash_to_scalar};
+use num_bigint::BigUint;
+use crate::{
+    check_satisfied, gen_test_params, prove_and_verify, sign, Bls12DSACircuit, DSAWitness,
+    ProverConfig,
+};
+
+// The circuit must agree with the native verifier on every tuple with
+// 0 < r, s < q, the range `validate_params` enforces before proving.
+// Genuine signatures exercise the accepting side, random (r, s) pairs the
+// rejecting one.
+#[test]
+fn test_circuit_agrees_with_native_verifier() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let mut proven = 0;
+    for bits in [8, 12, 16, 24, 32] {
+        let (params, keys) = gen_test_params(bits, &mut rng);
+        for i in 0..20 {
+            let msg = [bits as u8, i];
+            let h_x = hash_to_scalar(&Sha256::digest(msg), params.q);
+            let sig = sign(&msg, &keys, &params, &mut rng).expect("Signing failed");
+            let (r, s) = if i % 2 == 0 {
+                (sig.r, sig.s)
+            } else {
+                (rng.gen_range(1..params.q), rng.gen_range(1..params.q))
+            };
+            let native = dsa_verify_native(keys.y, h_x, r, s, params.p, params.q, params.g);
+            let circuit = Bls12DSACircuit::new(keys.y, h_x, r, s, params.p, params.q, params.g);
+            assert_eq!(
+                check_satisfied(circuit.clone()).unwrap(),
+                native,
+                "disagreement for {:?}, y = {}, h_x = {}, r = {}, s = {}",
+                params,
+                keys.y,
+                h_x,
+                r,
+                s
+            );
+            // A full proof is far slower, so only a few valid tuples get one
+            if native && proven < 3 {
+                let config = ProverConfig { seed: Some(proven), ..ProverConfig::default() };
+                assert!(prove_and_verify(&circuit, &config).expect("Pipeline failed"));
+                proven += 1;
+            }
+        }
+    }
+    assert_eq!(proven, 3);
+}
+
+// The native witness computation alone, without any R1CS: its final
+// comparison must match the reference verifier
+#[test]
+fn test_witness_computation_agrees_with_native_verifier() {
+    let mut rng = StdRng::seed_from_u64(1u64);
+    for bits in [8, 16, 32, 48, 63] {
+        let (params, keys) = gen_test_params(bits, &mut rng);
+        for i in 0..50u8 {
+            let msg = [i];
+            let (h_x, r, s) = if i % 2 == 0 {
+                let sig = sign(&msg, &keys, &params, &mut rng).expect("Signing failed");
+                (hash_to_scalar(&Sha256::digest(msg), params.q), sig.r, sig.s)
+            } else {
+                let q = params.q;
+                (rng.gen_range(0..q), rng.gen_range(1..q), rng.gen_range(1..q))
+            };
+            let [y, h, r_big, s_big, p, q, g] =
+                [keys.y, h_x, r, s, params.p, params.q, params.g].map(BigUint::from);
+            let witness = DSAWitness::compute(&y, &h, &r_big, &s_big, &p, &q, &g).unwrap();
+            assert_eq!(
+                witness.v_mod_q == witness.r_mod_q,
+                dsa_verify_native(keys.y, h_x, r, s, params.p, params.q, params.g),
+                "disagreement for {:?}, h_x = {}, r = {}, s = {}",
+                params,
+                h_x,
+                r,
+                s
+            );
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use crate::utils::{is_prime, modular_exponentiation};
+use crate::{
+    check_satisfied, gen_test_params, keygen, prove_and_verify, sign, verify, Bls12DSACircuit,
+    DSAParams, ParamError, ProverConfig, PublicKey, Signature, MIN_TEST_PARAM_BITS,
+};
+
+#[test]
+fn test_gen_test_params_are_valid() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    for bits in [MIN_TEST_PARAM_BITS, 8, 16, 32, 63] {
+        let (params, keys) = gen_test_params(bits, &mut rng);
+        assert_eq!(64 - params.p.leading_zeros(), bits, "p has the wrong width");
+        assert!(is_prime(params.p) && is_prime(params.q));
+        assert_eq!((params.p - 1) % params.q, 0);
+        assert_ne!(params.g, 1);
+        assert_eq!(modular_exponentiation(params.g, params.q, params.p), 1);
+        assert_eq!(keys.y, modular_exponentiation(params.g, keys.x, params.p));
+    }
+}
+
+#[test]
+fn test_signatures_satisfy_the_circuit() {
+    let mut rng = StdRng::seed_from_u64(1u64);
+    for bits in [8, 16, 32, 63] {
+        let (params, keys) = gen_test_params(bits, &mut rng);
+        let msg = b"pq-zkp test message";
+        let sig = sign(msg, &keys, &params, &mut rng).expect("Signing failed");
+        let circuit =
+            Bls12DSACircuit::from_message(msg, keys.y, sig.r, sig.s, params.p, params.q, params.g)
+                .unwrap();
+        assert_eq!(circuit.validate_params(), Ok(()));
+        assert!(check_satisfied(circuit).unwrap(), "signature rejected for {} bits", bits);
+    }
+}
+
+// The narrowest domains sign for every seed, and a domain where no nonce
+// can sign is reported instead of retried forever
+#[test]
+fn test_sign_at_minimum_width() {
+    for seed in 0..32 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (params, keys) = gen_test_params(MIN_TEST_PARAM_BITS, &mut rng);
+        assert!(params.q >= 5);
+        let sig = sign(b"small", &keys, &params, &mut rng).expect("Signing failed");
+        assert!(verify(keys.y, b"small", &sig, &params), "seed {}", seed);
+    }
+
+    // q = 2 forces g = p - 1, so r = g^k mod p mod 2 is 0 for every k
+    let params = DSAParams { p: 7, q: 2, g: 6 };
+    let keys = keygen(&params, &mut StdRng::seed_from_u64(0));
+    let result = sign(b"small", &keys, &params, &mut StdRng::seed_from_u64(0));
+    assert_eq!(result, Err(ParamError::NoValidNonce));
+}
+
+#[test]
+fn test_signature_for_other_message_is_rejected() {
+    let mut rng = StdRng::seed_from_u64(2u64);
+    let (params, keys) = gen_test_params(32, &mut rng);
+    let sig = sign(b"signed", &keys, &params, &mut rng).expect("Signing failed");
+    let circuit =
+        Bls12DSACircuit::from_message(b"forged", keys.y, sig.r, sig.s, params.p, params.q, params.g)
+            .unwrap();
+    assert!(!check_satisfied(circuit).unwrap());
+}
+
+#[test]
+fn test_native_verify() {
+    let mut rng = StdRng::seed_from_u64(3u64);
+    let (params, _) = gen_test_params(32, &mut rng);
+    let keys = keygen(&params, &mut rng);
+    assert_eq!(keys.y, modular_exponentiation(params.g, keys.x, params.p));
+
+    let sig = sign(b"signed", &keys, &params, &mut rng).expect("Signing failed");
+    assert!(verify(keys.y, b"signed", &sig, &params));
+    assert!(!verify(keys.y, b"forged", &sig, &params));
+    let other = keygen(&params, &mut rng);
+    assert_eq!(verify(other.y, b"signed", &sig, &params), other.y == keys.y);
+    assert!(!verify(keys.y, b"signed", &Signature { r: sig.r, s: 0 }, &params));
+    assert!(!verify(keys.y, b"signed", &Signature { r: sig.r + params.q, s: sig.s }, &params));
+}
+
+// Keygen, native signing and verification, then a Groth16 proof of the
+// very same signature
+#[test]
+fn test_native_signature_end_to_end() {
+    let mut rng = StdRng::seed_from_u64(4u64);
+    let (params, _) = gen_test_params(24, &mut rng);
+    let keys = keygen(&params, &mut rng);
+    let msg = b"end to end";
+    let sig = sign(msg, &keys, &params, &mut rng).expect("Signing failed");
+    assert!(verify(keys.y, msg, &sig, &params));
+
+    let circuit = Bls12DSACircuit::from_signature(keys.y, msg, &sig, &params);
+    let config = ProverConfig { seed: Some(4), ..ProverConfig::default() };
+    assert!(prove_and_verify(&circuit, &config).expect("Pipeline failed"));
+}
+
+#[test]
+fn test_from_parts_matches_flat_construction() {
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let sig = Signature { r: 758, s: 52 };
+    let flat = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let composed = Bls12DSACircuit::from_parts(&params, PublicKey(572), 789, &sig);
+    assert_eq!(composed, flat);
+    let converted: Bls12DSACircuit = (params, PublicKey(572), 789, sig).into();
+    assert_eq!(converted, flat);
+    assert!(check_satisfied(converted).unwrap());
+}
+
+#[test]
+fn test_is_prime() {
+    let primes: Vec<u64> = (0..60).filter(|&n| is_prime(n)).collect();
+    assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59]);
+    assert!(is_prime(18_446_744_073_709_551_557));
+    // Strong pseudoprime to bases 2, 3, 5 and 7
+    assert!(!is_prime(3_215_031_751));
+}
+
+#[test]
+fn test_params_diff() {
+    let params = DSAParams { p: 23, q: 11, g: 4 };
+    assert!(params.diff(&params).is_empty());
+    let other = DSAParams { p: 47, q: 23, g: 4 };
+    assert_eq!(params.diff(&other), vec![("p", 23, 47), ("q", 11, 23)]);
+
+    let circuit = Bls12DSACircuit::new(3, 2, 2, 2, 23, 11, 4);
+    assert_eq!(circuit.params(), Some(params));
+}
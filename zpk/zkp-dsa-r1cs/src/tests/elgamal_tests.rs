@@ -0,0 +1,81 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use num_bigint::BigUint;
+use crate::{CircuitError, ElGamalVerificationCircuit, ElGamalWitness};
+
+// p = 2039 with primitive root g = 7, key x = 127 (y = 503), and (r, s)
+// signing h = 1000 with k = 101
+fn valid_circuit() -> ElGamalVerificationCircuit<Fr> {
+    ElGamalVerificationCircuit::new(503, 1000, 33, 715, 2039, 7)
+}
+
+fn is_satisfied(circuit: ElGamalVerificationCircuit<Fr>) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn test_elgamal_verification() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = valid_circuit();
+    assert!(is_satisfied(circuit.clone()));
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &circuit.public_inputs(), &proof)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+#[test]
+fn test_elgamal_tampered_s_is_rejected() {
+    let mut circuit = valid_circuit();
+    circuit.s = Fr::from(716u64);
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn test_elgamal_modulus_below_two_is_an_error() {
+    for p in [0, 1] {
+        let circuit = ElGamalVerificationCircuit::<Fr>::new(503, 1000, 33, 715, p, 7);
+        assert_eq!(circuit.compute_witness(), Err(CircuitError::ModulusTooSmall));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        assert!(circuit.generate_constraints(cs).is_err());
+    }
+}
+
+#[test]
+fn test_elgamal_substituted_powers_are_rejected() {
+    let satisfied_with = |circuit: ElGamalVerificationCircuit<Fr>, witness: ElGamalWitness| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.with_witness(witness).generate_constraints(cs.clone()).expect("Synthesis failed");
+        cs.is_satisfied().unwrap()
+    };
+    let honest = valid_circuit().compute_witness().expect("Valid modulus");
+    assert!(satisfied_with(valid_circuit(), honest.clone()));
+
+    // g^h = y^r * r^s holds trivially for 1 = 1 * 1, whatever the signature
+    let mut tampered = valid_circuit();
+    tampered.s = Fr::from(716u64);
+    let ones = ElGamalWitness {
+        g_h: BigUint::from(1u32),
+        y_r: BigUint::from(1u32),
+        r_s: BigUint::from(1u32),
+    };
+    assert!(!satisfied_with(tampered, ones));
+
+    let forged = |value: &BigUint| value % 2038u32 + 1u32;
+    let cases = [
+        ElGamalWitness { g_h: forged(&honest.g_h), ..honest.clone() },
+        ElGamalWitness { y_r: forged(&honest.y_r), ..honest.clone() },
+        ElGamalWitness { r_s: forged(&honest.r_s), ..honest.clone() },
+    ];
+    for witness in cases {
+        assert!(!satisfied_with(valid_circuit(), witness));
+    }
+}
@@ -0,0 +1,80 @@
+use ark_bls12_381::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use crate::circuit::to_field;
+use crate::utils::hash_to_scalar_biguint;
+use crate::{
+    prove_and_verify, DSAParams, DSAVerificationCircuit, ProverConfig, PublicKey, Signature,
+};
+
+// A scaled-down FIPS 186-4 domain with L = 126 and N = 64, small enough for
+// p^2 to fit in the BLS12-381 scalar field. q is a 64-bit prime, p = 2kq + 1
+// a 126-bit prime, and g = 2^((p - 1) / q) mod p as in appendix A.2.1.
+const P: &str = "43901713085629518889726692228120180467";
+const Q: &str = "16753408044180871979";
+const G: &str = "35189341409188023024088165008885726101";
+// Key pair x, y = g^x mod p, and the signature on "abc" with nonce k
+const X: &str = "434291289340622139";
+const Y: &str = "12849707005871182227582121814900812303";
+const K: &str = "6736106760304658839";
+const R: &str = "12348186974419604892";
+const S: &str = "6543704749024725301";
+// Section 4.6: z is the leftmost 64 bits of SHA-256("abc"), h = z mod q
+const H: &str = "13436514500253700074";
+// Section 4.7 intermediates: w = s^-1 mod q, u1 = h * w mod q,
+// u2 = r * w mod q and v = (g^u1 * y^u2 mod p) mod q, where V_P is the
+// value before the final reduction mod q
+const W: &str = "12804646930621150293";
+const U1: &str = "9270533411207362758";
+const U2: &str = "9471387859251848381";
+const V_P: &str = "15594105508795350346710945091405770490";
+
+fn int(value: &str) -> BigUint {
+    BigUint::from_str(value).unwrap()
+}
+
+fn fips_circuit() -> DSAVerificationCircuit {
+    DSAVerificationCircuit::from_field_parts(
+        DSAParams { p: to_field(&int(P)), q: to_field(&int(Q)), g: to_field(&int(G)) },
+        PublicKey(to_field(&int(Y))),
+        to_field(&int(H)),
+        Signature { r: to_field(&int(R)), s: to_field(&int(S)) },
+    )
+}
+
+#[test]
+fn test_fips_vector_is_consistent() {
+    let (p, q, g) = (int(P), int(Q), int(G));
+    assert_eq!((&p - 1u32) % &q, BigUint::from(0u32));
+    assert_eq!(g.modpow(&q, &p), BigUint::from(1u32));
+    assert_eq!(int(Y), g.modpow(&int(X), &p));
+    assert_eq!(hash_to_scalar_biguint(&Sha256::digest(b"abc"), &q), int(H));
+    // r = (g^k mod p) mod q and s = k^-1 (h + x r) mod q
+    assert_eq!(g.modpow(&int(K), &p) % &q, int(R));
+    let k_inv = int(K).modinv(&q).unwrap();
+    assert_eq!(k_inv * (int(H) + int(X) * int(R)) % &q, int(S));
+    assert_eq!(fips_circuit().validate_params(), Ok(()));
+}
+
+#[test]
+fn test_fips_intermediates_match_witnesses() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    fips_circuit().generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+    // `enforce_signature` allocates w, u1, u2 and v as its first witnesses
+    let witnesses = cs.borrow().unwrap().witness_assignment.clone();
+    let expected: Vec<Fr> = [W, U1, U2, V_P].iter().map(|v| to_field(&int(v))).collect();
+    assert_eq!(witnesses[..4], expected[..]);
+    assert_eq!(int(V_P) % int(Q), int(R));
+}
+
+// Around 30 s even in release builds and minutes in debug ones, so it is
+// opt-in: `cargo test --release -- --ignored`
+#[test]
+#[ignore = "slow: full Groth16 setup and proof over a 126-bit modulus"]
+fn test_fips_vector_proves_and_verifies() {
+    let config = ProverConfig { seed: Some(0), ..ProverConfig::default() };
+    assert!(prove_and_verify(&fips_circuit(), &config).expect("Pipeline failed"));
+}
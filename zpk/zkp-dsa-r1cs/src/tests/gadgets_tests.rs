@@ -0,0 +1,332 @@
+use ark_bls12_381::Fr;
+use ark_ff::{FpParameters, PrimeField};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+use num_bigint::BigUint;
+use crate::gadgets::{
+    enforce_bits, enforce_canonical_bits, enforce_less_than, mod_reduce, mod_reduce_const, mul_mod,
+    mul_mod_const, pow_mod, pow_mod_const,
+};
+
+fn bits_cs(value: u64, num_bits: usize) -> ConstraintSystemRef<Fr> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let value_var = cs.new_witness_variable(|| Ok(Fr::from(value))).unwrap();
+    let bits = enforce_bits(&cs, value_var, num_bits).expect("Synthesis failed");
+    assert_eq!(bits.len(), num_bits);
+    cs
+}
+
+#[test]
+fn test_enforce_bits_in_range() {
+    assert!(bits_cs(0, 3).is_satisfied().unwrap());
+    assert!(bits_cs(5, 3).is_satisfied().unwrap());
+    assert!(bits_cs(7, 3).is_satisfied().unwrap());
+}
+
+#[test]
+fn test_enforce_bits_value_too_wide() {
+    assert!(!bits_cs(8, 3).is_satisfied().unwrap());
+    assert!(!bits_cs(u64::MAX, 63).is_satisfied().unwrap());
+}
+
+fn less_than_cs(a: u64, b: u64, bit_width: usize) -> ConstraintSystemRef<Fr> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let a_var = cs.new_witness_variable(|| Ok(Fr::from(a))).unwrap();
+    let b_var = cs.new_witness_variable(|| Ok(Fr::from(b))).unwrap();
+    enforce_less_than(&cs, a_var, b_var, bit_width).expect("Synthesis failed");
+    cs
+}
+
+#[test]
+fn test_enforce_less_than_boundaries() {
+    assert!(!less_than_cs(6, 6, 3).is_satisfied().unwrap(), "a == b must fail");
+    assert!(less_than_cs(5, 6, 3).is_satisfied().unwrap(), "a == b - 1 must pass");
+    assert!(less_than_cs(0, 7, 3).is_satisfied().unwrap());
+    assert!(!less_than_cs(7, 6, 3).is_satisfied().unwrap());
+}
+
+// `dividend_val` is what the gadget computes its witnesses from; it only
+// differs from `dividend` as an integer when a test forges the reduction
+fn reduce_cs(dividend: u64, dividend_val: &BigUint, modulus: u64) -> (ConstraintSystemRef<Fr>, Fr) {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let dividend_var = cs.new_witness_variable(|| Ok(Fr::from(dividend))).unwrap();
+    let modulus_var = cs.new_input_variable(|| Ok(Fr::from(modulus))).unwrap();
+    let modulus_val = BigUint::from(modulus);
+    let remainder_var = mod_reduce(&cs, dividend_var, dividend_val, 64, modulus_var, &modulus_val)
+        .expect("Synthesis failed");
+    let remainder = cs.assigned_value(remainder_var).unwrap();
+    (cs, remainder)
+}
+
+fn mod_reduce_cs(dividend: u64, modulus: u64) -> (ConstraintSystemRef<Fr>, Fr) {
+    reduce_cs(dividend, &BigUint::from(dividend), modulus)
+}
+
+#[test]
+fn test_mod_reduce() {
+    for (dividend, modulus) in [(0u64, 7u64), (6, 7), (7, 7), (50, 7), (u64::MAX, 1019)] {
+        let (cs, remainder) = mod_reduce_cs(dividend, modulus);
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(remainder, Fr::from(dividend % modulus));
+    }
+}
+
+// 10 + k * |Fr| is 10 in the field, but the gadget divides it as an
+// integer, so the quotient it assigns is the field quotient
+// (10 - 5) / 7 for the forged remainder 5. Only the bound on the quotient
+// rejects it.
+#[test]
+fn test_mod_reduce_rejects_remainder_forged_with_field_quotient() {
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let k = (0u32..7).find(|&k| (10u32 + &modulus * k) % 7u32 == BigUint::from(5u32)).unwrap();
+    let (cs, remainder) = reduce_cs(10, &(10u32 + modulus * k), 7);
+    assert_eq!(remainder, Fr::from(5u64));
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_mod_reduce_rejects_dividend_above_bound() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let dividend_var = cs.new_witness_variable(|| Ok(Fr::from(1u64 << 20))).unwrap();
+    let modulus_var = cs.new_input_variable(|| Ok(Fr::from(7u64))).unwrap();
+    let dividend_val = BigUint::from(1u64 << 20);
+    mod_reduce(&cs, dividend_var, &dividend_val, 8, modulus_var, &BigUint::from(7u64)).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+
+    // A bound this close to the field size could let the sum wrap
+    let bits = <Fr as PrimeField>::Params::MODULUS_BITS as usize - 1;
+    assert!(mod_reduce(&cs, dividend_var, &dividend_val, bits, modulus_var, &BigUint::from(7u64))
+        .is_err());
+}
+
+#[test]
+fn test_mul_mod() {
+    let cases = [(3u64, 5u64, 7u64), (0, 9, 7), (1018, 1018, 1019), (2038, 2038, 2039)];
+    for (a, b, modulus) in cases {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a_var = cs.new_witness_variable(|| Ok(Fr::from(a))).unwrap();
+        let b_var = cs.new_witness_variable(|| Ok(Fr::from(b))).unwrap();
+        let modulus_var = cs.new_input_variable(|| Ok(Fr::from(modulus))).unwrap();
+        let (a_val, b_val, modulus_val) = (BigUint::from(a), BigUint::from(b), BigUint::from(modulus));
+        let result_var = mul_mod(&cs, a_var, &a_val, b_var, &b_val, modulus_var, &modulus_val)
+            .expect("Synthesis failed");
+        assert!(cs.is_satisfied().unwrap());
+        let expected = a_val * b_val % modulus_val;
+        assert_eq!(cs.assigned_value(result_var).unwrap(), Fr::from(expected));
+    }
+}
+
+// Operands wider than the modulus would need a wider quotient than
+// `mul_mod` allows
+#[test]
+fn test_mul_mod_rejects_operands_wider_than_modulus() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let a_var = cs.new_witness_variable(|| Ok(Fr::from(u64::MAX))).unwrap();
+    let modulus_var = cs.new_input_variable(|| Ok(Fr::from(2039u64))).unwrap();
+    let a_val = BigUint::from(u64::MAX);
+    mul_mod(&cs, a_var, &a_val, a_var, &a_val, modulus_var, &BigUint::from(2039u64)).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_mul_mod_rejects_inconsistent_values() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let a_var = cs.new_witness_variable(|| Ok(Fr::from(3u64))).unwrap();
+    let b_var = cs.new_witness_variable(|| Ok(Fr::from(5u64))).unwrap();
+    let modulus_var = cs.new_input_variable(|| Ok(Fr::from(7u64))).unwrap();
+    // The product witness is computed from b = 6, which disagrees with b_var
+    mul_mod(&cs, a_var, &BigUint::from(3u64), b_var, &BigUint::from(6u64), modulus_var, &BigUint::from(7u64))
+        .expect("Synthesis failed");
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+fn pow_mod_cs(base: u64, exp: u64, num_bits: usize, modulus: u64) -> (ConstraintSystemRef<Fr>, Fr) {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let base_var = cs.new_input_variable(|| Ok(Fr::from(base))).unwrap();
+    let exp_var = cs.new_witness_variable(|| Ok(Fr::from(exp))).unwrap();
+    let modulus_var = cs.new_input_variable(|| Ok(Fr::from(modulus))).unwrap();
+    let result_var = pow_mod(
+        &cs,
+        base_var,
+        &BigUint::from(base),
+        exp_var,
+        &BigUint::from(exp),
+        num_bits,
+        modulus_var,
+        &BigUint::from(modulus),
+    )
+    .expect("Synthesis failed");
+    let result = cs.assigned_value(result_var).unwrap();
+    (cs, result)
+}
+
+#[test]
+fn test_pow_mod() {
+    let cases = [(4u64, 0u64, 2039u64), (4, 1, 2039), (4, 1018, 2039), (2039, 5, 2039), (3, 1000, 7)];
+    for (base, exp, modulus) in cases {
+        let (cs, result) = pow_mod_cs(base, exp, 10, modulus);
+        assert!(cs.is_satisfied().unwrap(), "base={}, exp={}", base, exp);
+        let expected = BigUint::from(base).modpow(&BigUint::from(exp), &BigUint::from(modulus));
+        assert_eq!(result, Fr::from(expected));
+    }
+}
+
+#[test]
+fn test_pow_mod_exponent_too_wide() {
+    let (cs, _) = pow_mod_cs(4, 1024, 10, 2039);
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+// `pow_mod_cs` allocates the exponent as witness 0 and `enforce_bits` then
+// allocates its bits as witnesses 1..=num_bits
+#[test]
+fn test_pow_mod_bits_are_bound_to_exponent() {
+    let (cs, _) = pow_mod_cs(4, 6, 10, 2039);
+    assert!(cs.is_satisfied().unwrap());
+    // Every exponentiation witness is honest for 4^6, so only the
+    // decomposition constraint can notice the exponent now claims 5
+    cs.borrow_mut().unwrap().witness_assignment[0] = Fr::from(5u64);
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_pow_mod_rejects_flipped_exponent_bit() {
+    for bit in 1..=10 {
+        let (cs, _) = pow_mod_cs(4, 6, 10, 2039);
+        let flipped = Fr::from(1u64) - cs.borrow().unwrap().witness_assignment[bit];
+        cs.borrow_mut().unwrap().witness_assignment[bit] = flipped;
+        assert!(!cs.is_satisfied().unwrap(), "flipping bit {} went unnoticed", bit - 1);
+    }
+}
+
+#[test]
+fn test_const_modulus_gadgets_match_variable_ones() {
+    for (a, b, modulus) in [(3u64, 5u64, 7u64), (0, 9, 7), (1018, 1018, 1019), (2038, 2, 2039)] {
+        let (a_val, b_val, modulus_val) = (BigUint::from(a), BigUint::from(b), BigUint::from(modulus));
+        let n = modulus_val.bits() as usize;
+        // A 2n-bit dividend has an (n + 1)-bit quotient, whose decomposition
+        // costs n + 2 constraints
+        let quotient_check = n + 2;
+
+        let var_cs = ConstraintSystem::<Fr>::new_ref();
+        let a_var = var_cs.new_witness_variable(|| Ok(Fr::from(a))).unwrap();
+        let b_var = var_cs.new_witness_variable(|| Ok(Fr::from(b))).unwrap();
+        let modulus_var = var_cs.new_input_variable(|| Ok(Fr::from(modulus))).unwrap();
+        let start = var_cs.num_constraints();
+        let reduced = mod_reduce(&var_cs, a_var, &a_val, 2 * n, modulus_var, &modulus_val).unwrap();
+        assert_eq!(var_cs.num_constraints() - start, 2 * n + 5 + quotient_check);
+        let start = var_cs.num_constraints();
+        let product = mul_mod(&var_cs, a_var, &a_val, b_var, &b_val, modulus_var, &modulus_val).unwrap();
+        assert_eq!(var_cs.num_constraints() - start, 2 * n + 6 + quotient_check);
+
+        let const_cs = ConstraintSystem::<Fr>::new_ref();
+        let a_var = const_cs.new_witness_variable(|| Ok(Fr::from(a))).unwrap();
+        let b_var = const_cs.new_witness_variable(|| Ok(Fr::from(b))).unwrap();
+        let start = const_cs.num_constraints();
+        let reduced_const = mod_reduce_const(&const_cs, a_var, &a_val, 2 * n, &modulus_val).unwrap();
+        assert_eq!(const_cs.num_constraints() - start, 2 * n + 4 + quotient_check);
+        let start = const_cs.num_constraints();
+        let product_const = mul_mod_const(&const_cs, a_var, &a_val, b_var, &b_val, &modulus_val).unwrap();
+        assert_eq!(const_cs.num_constraints() - start, 2 * n + 4 + quotient_check);
+
+        assert!(var_cs.is_satisfied().unwrap());
+        assert!(const_cs.is_satisfied().unwrap());
+        assert_eq!(const_cs.assigned_value(reduced_const), var_cs.assigned_value(reduced));
+        assert_eq!(const_cs.assigned_value(product_const), var_cs.assigned_value(product));
+        assert_eq!(const_cs.assigned_value(product_const).unwrap(), Fr::from(a_val * b_val % modulus_val));
+    }
+}
+
+#[test]
+fn test_mul_mod_const_rejects_inconsistent_values() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let a_var = cs.new_witness_variable(|| Ok(Fr::from(3u64))).unwrap();
+    let b_var = cs.new_witness_variable(|| Ok(Fr::from(5u64))).unwrap();
+    mul_mod_const(&cs, a_var, &BigUint::from(3u64), b_var, &BigUint::from(6u64), &BigUint::from(7u64))
+        .expect("Synthesis failed");
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_pow_mod_const() {
+    let (modulus, num_bits) = (2039u64, 10);
+    for (base, exp) in [(4u64, 0u64), (4, 1), (4, 1018), (2038, 777)] {
+        let (_, expected) = pow_mod_cs(base, exp, num_bits, modulus);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let base_var = cs.new_input_variable(|| Ok(Fr::from(base))).unwrap();
+        let exp_var = cs.new_witness_variable(|| Ok(Fr::from(exp))).unwrap();
+        let result_var = pow_mod_const(
+            &cs,
+            base_var,
+            &BigUint::from(base),
+            exp_var,
+            &BigUint::from(exp),
+            num_bits,
+            &BigUint::from(modulus),
+        )
+        .expect("Synthesis failed");
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.assigned_value(result_var).unwrap(), expected);
+    }
+
+    // 2 constraints saved per multiplication or squaring, plus one in the
+    // initial base reduction
+    let (var_cs, _) = pow_mod_cs(4, 1018, num_bits, modulus);
+    let const_cs = ConstraintSystem::<Fr>::new_ref();
+    let base_var = const_cs.new_input_variable(|| Ok(Fr::from(4u64))).unwrap();
+    let exp_var = const_cs.new_witness_variable(|| Ok(Fr::from(1018u64))).unwrap();
+    let (base_val, exp_val) = (BigUint::from(4u64), BigUint::from(1018u64));
+    pow_mod_const(&const_cs, base_var, &base_val, exp_var, &exp_val, num_bits, &BigUint::from(modulus))
+        .unwrap();
+    assert_eq!(var_cs.num_constraints() - const_cs.num_constraints(), 2 * (2 * num_bits - 1) + 1);
+}
+
+#[test]
+fn test_enforce_canonical_bits() {
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    for value in [Fr::from(0u64), Fr::from(5u64), Fr::from(&modulus - 1u32)] {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let value_var = cs.new_witness_variable(|| Ok(value)).unwrap();
+        let bits = enforce_canonical_bits(&cs, value_var).expect("Synthesis failed");
+        assert_eq!(bits.len(), 255);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
+
+#[test]
+fn test_enforce_canonical_bits_rejects_the_other_decomposition() {
+    // 1 + r still fits in 255 bits, and its bits sum to 1 in the field
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let alias = &modulus + 1u32;
+    let max = &modulus - 1u32;
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let value_var = cs.new_witness_variable(|| Ok(Fr::from(1u64))).unwrap();
+    enforce_canonical_bits(&cs, value_var).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+
+    // Swap in the bits of 1 + r and recompute the running products from them,
+    // so only the comparison with r - 1 can fail. Witness 0 is the value,
+    // 1..=255 are the bits and the products follow in allocation order.
+    {
+        let mut system = cs.borrow_mut().unwrap();
+        let witness = &mut system.witness_assignment;
+        for i in 0..255 {
+            witness[1 + i] = Fr::from(u64::from(alias.bit(i as u64)));
+        }
+        let mut next = 256;
+        let mut run: Option<Fr> = None;
+        for i in (0..255).rev() {
+            if max.bit(i as u64) {
+                let bit = witness[1 + i];
+                run = Some(match run {
+                    Some(run) => {
+                        witness[next] = run * bit;
+                        next += 1;
+                        run * bit
+                    }
+                    None => bit,
+                });
+            }
+        }
+    }
+    assert!(!cs.is_satisfied().unwrap());
+}
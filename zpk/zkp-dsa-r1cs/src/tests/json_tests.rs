@@ -0,0 +1,69 @@
+use ark_bls12_381::Fr;
+use std::path::{Path, PathBuf};
+use ark_ff::{FpParameters, PrimeField};
+use num_bigint::BigUint;
+use std::str::FromStr;
+use crate::{Bls12DSACircuit, DSAInputs};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures").join(name)
+}
+
+#[test]
+fn test_from_json_fixture() {
+    let circuit = Bls12DSACircuit::from_json(&fixture("dsa_inputs.json"))
+        .expect("Loading fixture failed");
+    assert_eq!(circuit, Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2));
+}
+
+#[test]
+fn test_json_round_trip() {
+    // p = 2^127 - 1 does not fit in a JSON number
+    let mut circuit = Bls12DSACircuit::new(1, 5, 0, 7, 0, 0, 3);
+    circuit.params.p = Fr::from_str("170141183460469231731687303715884105727").unwrap();
+    circuit.params.q = Fr::from_str("618970019642690137449562111").unwrap();
+    circuit.sig.r = Fr::from_str("559224695919987956436029337").unwrap();
+
+    let path = std::env::temp_dir().join(format!("zkp-dsa-r1cs-json-{}.json", std::process::id()));
+    circuit.to_json(&path).expect("Writing JSON failed");
+    let loaded = Bls12DSACircuit::from_json(&path).expect("Reading JSON failed");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, circuit);
+    assert_eq!(DSAInputs::from(&loaded).p, "170141183460469231731687303715884105727");
+}
+
+#[test]
+fn test_invalid_decimal_is_rejected() {
+    let inputs = DSAInputs {
+        y: "2".into(),
+        h_x: "2".into(),
+        r: "0x2".into(),
+        s: "1".into(),
+        p: "7".into(),
+        q: "3".into(),
+        g: "2".into(),
+    };
+    assert!(inputs.to_circuit::<Fr>().is_err());
+}
+
+#[test]
+fn test_value_above_modulus_is_rejected() {
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let mut inputs = DSAInputs {
+        y: "2".into(),
+        h_x: "2".into(),
+        r: "2".into(),
+        s: "1".into(),
+        p: "7".into(),
+        q: "3".into(),
+        g: "2".into(),
+    };
+    inputs.h_x = (&modulus - 1u32).to_string();
+    assert!(inputs.to_circuit::<Fr>().is_ok());
+    for value in [modulus.clone(), &modulus + 2u32] {
+        inputs.h_x = value.to_string();
+        let err = inputs.to_circuit::<Fr>().expect_err("Value should not fit");
+        assert!(err.to_string().contains("`h_x`"), "unexpected error: {}", err);
+    }
+}
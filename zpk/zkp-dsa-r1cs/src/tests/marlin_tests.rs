@@ -0,0 +1,34 @@
+// Only built with `--features marlin`
+#![cfg(feature = "marlin")]
+use ark_bls12_381::Fr;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use crate::{Bls12DSACircuit, DSAProver, MarlinIpaProver};
+
+#[test]
+fn test_marlin_prove_and_verify() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let (pk, vk) = MarlinIpaProver::setup(&circuit, &mut rng).expect("Setup failed");
+    let proof = MarlinIpaProver::prove(&pk, &circuit, &mut rng).expect("Proving failed");
+    let inputs = circuit.public_inputs();
+    assert!(MarlinIpaProver::verify(&vk, &inputs, &proof).expect("Verification failed"));
+
+    let mut wrong_inputs = inputs;
+    wrong_inputs[1] = Fr::from(1u64);
+    assert!(!MarlinIpaProver::verify(&vk, &wrong_inputs, &proof).unwrap_or(false));
+}
+
+// Nothing secret is sampled, so the seed has no effect on the keys
+#[test]
+fn test_marlin_setup_ignores_rng() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let vk_bytes = |seed: u64| {
+        let (_, vk) = MarlinIpaProver::setup(&circuit, &mut StdRng::seed_from_u64(seed))
+            .expect("Setup failed");
+        let mut bytes = Vec::new();
+        vk.serialize(&mut bytes).expect("Serialization failed");
+        bytes
+    };
+    assert_eq!(vk_bytes(1), vk_bytes(2));
+}
@@ -0,0 +1,28 @@
+// Only built with `--features metrics`
+#![cfg(feature = "metrics")]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use crate::{prove_and_verify, Bls12DSACircuit, ProverConfig};
+
+#[test]
+fn test_pipeline_records_durations() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+        let config = ProverConfig { seed: Some(0), ..ProverConfig::default() };
+        assert!(prove_and_verify(&circuit, &config).expect("Pipeline failed"));
+    });
+
+    let mut names: Vec<String> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            assert!(matches!(value, DebugValue::Histogram(ref samples) if samples.len() == 1));
+            key.key().name().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, ["pq_zkp.prove_seconds", "pq_zkp.setup_seconds", "pq_zkp.verify_seconds"]);
+}
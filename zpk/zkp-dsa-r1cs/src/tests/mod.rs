@@ -1,2 +1,46 @@
 #[cfg(test)]
+pub mod analysis_tests;
+#[cfg(test)]
+pub mod arbitrary_tests;
+#[cfg(test)]
+pub mod batch_tests;
+#[cfg(test)]
 pub mod circuit_tests;
+#[cfg(test)]
+pub mod debug_constraints_tests;
+#[cfg(test)]
+pub mod der_tests;
+#[cfg(test)]
+pub mod differential_tests;
+#[cfg(test)]
+pub mod dsa_tests;
+#[cfg(test)]
+pub mod elgamal_tests;
+#[cfg(test)]
+pub mod fips_tests;
+#[cfg(test)]
+pub mod gadgets_tests;
+#[cfg(test)]
+pub mod json_tests;
+#[cfg(test)]
+pub mod marlin_tests;
+#[cfg(test)]
+pub mod metrics_tests;
+#[cfg(test)]
+pub mod namespace_tests;
+#[cfg(test)]
+pub mod negative_tests;
+#[cfg(test)]
+pub mod poseidon_tests;
+#[cfg(test)]
+pub mod prover_tests;
+#[cfg(test)]
+pub mod rerandomize_tests;
+#[cfg(test)]
+pub mod serialization_tests;
+#[cfg(test)]
+pub mod silent_tests;
+#[cfg(test)]
+pub mod solidity_tests;
+#[cfg(test)]
+pub mod utils_tests;
@@ -0,0 +1,37 @@
+use ark_bls12_381::Fr;
+use ark_relations::r1cs::{
+    ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, TracingMode,
+};
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::layer::SubscriberExt;
+use crate::Bls12DSACircuit;
+
+// Synthesizes `circuit` with constraint tracing enabled. Traces are only
+// recorded while the returned guard is alive.
+fn traced_synthesis(circuit: Bls12DSACircuit) -> (ConstraintSystemRef<Fr>, DefaultGuard) {
+    let mut layer = ConstraintLayer::default();
+    layer.mode = TracingMode::OnlyConstraints;
+    let guard = tracing::subscriber::set_default(tracing_subscriber::Registry::default().with(layer));
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    (cs, guard)
+}
+
+#[test]
+fn test_unsatisfied_constraint_names_its_block() {
+    // s tampered from 52 to 53: only the final comparison breaks
+    let (cs, _guard) = traced_synthesis(Bls12DSACircuit::new(572, 789, 758, 53, 2039, 1019, 4));
+    let trace = cs.which_is_unsatisfied().unwrap().expect("Tampered signature is unsatisfied");
+    assert!(trace.contains("final_equality"), "unexpected trace: {}", trace);
+}
+
+#[test]
+fn test_tampered_witness_names_its_block() {
+    let (cs, _guard) = traced_synthesis(Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4));
+    assert_eq!(cs.which_is_unsatisfied().unwrap(), None);
+    // The first witness is w = s^-1 mod q, which the `ws_reduction` block
+    // checks first
+    cs.borrow_mut().unwrap().witness_assignment[0] += Fr::from(1u64);
+    let trace = cs.which_is_unsatisfied().unwrap().expect("Tampered w is unsatisfied");
+    assert!(trace.contains("ws_reduction"), "unexpected trace: {}", trace);
+}
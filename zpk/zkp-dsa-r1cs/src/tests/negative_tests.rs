@@ -0,0 +1,175 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{FpParameters, PrimeField};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use num_bigint::BigUint;
+use std::panic::{self, AssertUnwindSafe};
+use crate::{Bls12DSACircuit, DSAVerificationCircuit, WitnessOverrides};
+
+// p = 2039 = 2q + 1 with q = 1019 and g = 4 of order q. The key is x = 123
+// and (r, s) signs h = 789 with k = 456. The group is large enough that the
+// tampered values below don't verify by coincidence.
+fn valid_circuit() -> DSAVerificationCircuit {
+    Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4)
+}
+
+fn assert_rejected(tampered: DSAVerificationCircuit) {
+    let mut rng = StdRng::seed_from_u64(0u64);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    tampered.clone().generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(!cs.is_satisfied().unwrap(), "Tampered witness must not satisfy the circuit");
+
+    // Keys come from the honest circuit; the tampered one has the same shape.
+    // Debug builds of ark-groth16 assert satisfiability inside `prove`, so an
+    // unsatisfiable witness either panics there or yields a rejected proof.
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(valid_circuit(), &mut rng)
+        .expect("Setup failed");
+    let proving = panic::catch_unwind(AssertUnwindSafe(|| {
+        Groth16::<Bls12_381>::prove(&pk, tampered.clone(), &mut rng)
+    }));
+    if let Ok(proof) = proving {
+        let proof = proof.expect("Proving failed");
+        let is_valid = Groth16::<Bls12_381>::verify(&vk, &tampered.public_inputs(), &proof)
+            .expect("Verification failed");
+        assert!(!is_valid, "Proof of a tampered signature must not verify");
+    }
+}
+
+#[test]
+fn test_valid_circuit_is_satisfied() {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    valid_circuit().generate_constraints(cs.clone()).expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_wrong_r_is_rejected() {
+    let mut circuit = valid_circuit();
+    circuit.sig.r = Fr::from(759u64);
+    assert_rejected(circuit);
+}
+
+#[test]
+fn test_wrong_s_is_rejected() {
+    let mut circuit = valid_circuit();
+    circuit.sig.s = Fr::from(53u64);
+    assert_rejected(circuit);
+}
+
+#[test]
+fn test_wrong_public_key_is_rejected() {
+    let mut circuit = valid_circuit();
+    circuit.key.0 = Fr::from(573u64);
+    assert_rejected(circuit);
+}
+
+#[test]
+fn test_mismatched_message_hash_is_rejected() {
+    let mut circuit = valid_circuit();
+    circuit.h_x = Fr::from(790u64);
+    assert_rejected(circuit);
+}
+
+#[test]
+fn test_perturbed_public_inputs_are_rejected() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = valid_circuit();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let public_inputs = circuit.public_inputs();
+    assert!(Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof).unwrap());
+
+    // Substituting any single element breaks the binding
+    for i in 0..public_inputs.len() {
+        let mut perturbed = public_inputs.clone();
+        perturbed[i] += Fr::from(1u64);
+        let is_valid = Groth16::<Bls12_381>::verify(&vk, &perturbed, &proof)
+            .expect("Verification failed");
+        assert!(!is_valid, "Perturbing input {} must be rejected", i);
+    }
+
+    // So does supplying the inputs in the wrong order
+    let mut reordered = public_inputs.clone();
+    reordered.swap(2, 3);
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &reordered, &proof)
+        .expect("Verification failed");
+    assert!(!is_valid, "Reordered inputs must be rejected");
+}
+
+fn satisfied_with(overrides: WitnessOverrides) -> bool {
+    circuit_satisfied_with(valid_circuit(), overrides)
+}
+
+fn circuit_satisfied_with(circuit: DSAVerificationCircuit, overrides: WitnessOverrides) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit
+        .with_witness_override(overrides)
+        .generate_constraints(cs.clone())
+        .expect("Synthesis failed");
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn test_every_forged_intermediate_is_rejected() {
+    assert!(satisfied_with(WitnessOverrides::default()));
+
+    let honest = valid_circuit().compute_witness().expect("Valid signature");
+    let forged = |value: &num_bigint::BigUint| Some(value + 1u32);
+    let cases = [
+        ("w", WitnessOverrides { w: forged(&honest.w), ..Default::default() }),
+        ("u1", WitnessOverrides { u1: forged(&honest.u1), ..Default::default() }),
+        ("u2", WitnessOverrides { u2: forged(&honest.u2), ..Default::default() }),
+        ("g_u1", WitnessOverrides { g_u1: forged(&honest.g_u1), ..Default::default() }),
+        ("y_u2", WitnessOverrides { y_u2: forged(&honest.y_u2), ..Default::default() }),
+        ("v", WitnessOverrides { v: forged(&honest.v), ..Default::default() }),
+        ("v_mod_q", WitnessOverrides { v_mod_q: forged(&honest.v_mod_q), ..Default::default() }),
+        ("r_mod_q", WitnessOverrides { r_mod_q: forged(&honest.r_mod_q), ..Default::default() }),
+    ];
+    for (name, overrides) in cases {
+        assert!(!satisfied_with(overrides), "forged {} must not satisfy the circuit", name);
+    }
+}
+
+#[test]
+fn test_consistent_forgery_of_the_final_comparison_is_rejected() {
+    // Forging v_mod_q and r_mod_q to the same wrong value still breaks the
+    // reductions that tie them to v and r
+    let honest = valid_circuit().compute_witness().expect("Valid signature");
+    let fake = (&honest.v_mod_q + 1u32) % 1019u32;
+    let overrides = WitnessOverrides {
+        v_mod_q: Some(fake.clone()),
+        r_mod_q: Some(fake),
+        ..Default::default()
+    };
+    assert!(!satisfied_with(overrides));
+}
+
+#[test]
+fn test_remainder_forged_with_field_quotient_is_rejected() {
+    // v + k * |Fr| is the same field element as v, so it passes the
+    // reduction mod p, but as an integer it reduces mod q to whatever k
+    // picks. Choosing k so that v mod q lands on r mod q would make an
+    // invalid signature verify if the quotient of that reduction could
+    // exceed the field.
+    let mut circuit = valid_circuit();
+    circuit.sig.s = Fr::from(53u64);
+    let honest = circuit.compute_witness().expect("s is invertible");
+    assert_ne!(honest.v_mod_q, honest.r_mod_q);
+    let q = BigUint::from(1019u32);
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let modulus_inverse = modulus.modpow(&(&q - 2u32), &q);
+    let k = (&honest.r_mod_q + &q - &honest.v % &q) * modulus_inverse % &q;
+    let v = &honest.v + k * modulus;
+    assert_eq!(&v % &q, honest.r_mod_q);
+    let overrides = WitnessOverrides {
+        v: Some(v),
+        v_mod_q: Some(honest.r_mod_q.clone()),
+        ..Default::default()
+    };
+    assert!(!circuit_satisfied_with(circuit, overrides));
+}
@@ -0,0 +1,118 @@
+// Only built with `--features poseidon`
+#![cfg(feature = "poseidon")]
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use num_bigint::BigUint;
+use std::str::FromStr;
+use crate::poseidon::poseidon;
+use crate::utils::{dsa_sign_components, poseidon_hash_to_scalar};
+use crate::{gen_test_params, poseidon_digest, Bls12DSACircuit, PoseidonDSACircuit};
+
+fn message() -> Vec<Fr> {
+    vec![Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)]
+}
+
+// Known answers from ark-crypto-primitives 0.4: its Grain LFSR constants
+// for rate 5 over BLS12-381 Fr, and `PoseidonSponge` absorbing the length
+// and then the message and squeezing one element
+#[test]
+fn test_poseidon_vectors() {
+    let expected = |s: &str| Fr::from(BigUint::from_str(s).unwrap());
+    let params = poseidon();
+    assert_eq!(
+        params.round_keys[0][0],
+        expected("24877380261526996562448766783081897666376381975344509826094208368479247894723")
+    );
+    assert_eq!(
+        params.round_keys[64][5],
+        expected("49690037484548538794132847925320307816926558288091609454049541610446434292052")
+    );
+    assert_eq!(
+        params.mds[0][0],
+        expected("30022080821787948421423927053079656488514459012053372877891553084525866347732")
+    );
+    assert_eq!(
+        params.mds[5][5],
+        expected("19434308571857688222022934651051352199196434933024845264431243803233428952055")
+    );
+
+    assert_eq!(
+        poseidon_digest(&message()),
+        expected("40604634446294540225043405933321388535607260215934584307553317342096342390188")
+    );
+    assert_eq!(
+        poseidon_digest(&[]),
+        expected("12817800792780713224147891279303204992401713902245792564565589586002112595802")
+    );
+    // Filling one block exactly, and spilling into a second
+    let elements = |range: std::ops::Range<u64>| range.map(Fr::from).collect::<Vec<_>>();
+    assert_eq!(
+        poseidon_digest(&elements(10..14)),
+        expected("15480247989766473208574429744023716598941917947413125581715031057285192429783")
+    );
+    assert_eq!(
+        poseidon_digest(&elements(10..17)),
+        expected("306996834257993245006628187432327950463319317366865135621938375911577248710")
+    );
+    assert_eq!(poseidon_hash_to_scalar(&message(), 1019), Fr::from(428u64));
+}
+
+#[test]
+fn test_poseidon_digest_binds_length() {
+    let msg = message();
+    let mut padded = msg.clone();
+    padded.push(Fr::from(0u64));
+    assert_ne!(poseidon_digest(&msg), poseidon_digest(&padded));
+    assert_ne!(poseidon_digest(&[]), poseidon_digest(&[Fr::from(0u64)]));
+}
+
+// A genuine signature over the Poseidon hash of `msg`
+fn signed_circuit(msg: Vec<Fr>) -> PoseidonDSACircuit {
+    let mut rng = StdRng::seed_from_u64(5);
+    let (params, key) = gen_test_params(16, &mut rng);
+    let h: BigUint = poseidon_hash_to_scalar(&msg, params.q).into();
+    let h = u64::try_from(h).expect("h_x is below q");
+    let (r, s) = (2..params.q)
+        .find_map(|k| dsa_sign_components(k, key.x, h, params.p, params.q, params.g))
+        .expect("Some nonce signs");
+    Bls12DSACircuit::new(key.y, 0, r, s, params.p, params.q, params.g).with_poseidon_message(msg)
+}
+
+fn is_satisfied(circuit: PoseidonDSACircuit) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Synthesis failed");
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn test_poseidon_circuit_hashes_in_circuit() {
+    for len in [0, 1, 3, 4, 7] {
+        let msg: Vec<Fr> = (0..len).map(|i| Fr::from(i as u64 + 10)).collect();
+        let circuit = signed_circuit(msg);
+        assert_eq!(circuit.public_inputs().len(), 6 + len);
+        assert!(is_satisfied(circuit), "message of length {}", len);
+    }
+
+    // The same signature on a different message no longer verifies
+    let mut circuit = signed_circuit(message());
+    circuit.msg[0] += Fr::from(1u64);
+    assert!(!is_satisfied(circuit.clone()));
+    // and re-deriving h_x for that message does not help either
+    circuit = circuit.circuit.with_poseidon_message(vec![Fr::from(4u64); 3]);
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn test_poseidon_circuit_proves() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let circuit = signed_circuit(message());
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+    let mut inputs = circuit.public_inputs();
+    assert!(Groth16::<Bls12_381>::verify(&vk, &inputs, &proof).unwrap());
+    *inputs.last_mut().unwrap() += Fr::from(1u64);
+    assert!(!Groth16::<Bls12_381>::verify(&vk, &inputs, &proof).unwrap());
+}
@@ -0,0 +1,233 @@
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use ark_bls12_381::Fr;
+use crate::{
+    derive_rng, expected_public_inputs, prepare_public_inputs, prepare_vk, proof_to_bytes,
+    prove_many, prove_proof, setup_keys, verify_batch, verify_or_explain, verify_prepared,
+    verify_serialized, verify_stream, verify_with_prepared_inputs, verifying_key_to_bytes,
+    write_proof_frame, Bls12DSACircuit, DSAInputs, DSAProver, Groth16Prover, PqZkpError,
+    VerificationFailure,
+};
+
+#[test]
+fn test_fixed_seed_is_reproducible() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, _) = setup_keys(&circuit, Some(7)).expect("Setup failed");
+    let first = prove_proof(&pk, &circuit, Some(7)).expect("Proving failed");
+    let second = prove_proof(&pk, &circuit, Some(7)).expect("Proving failed");
+    assert_eq!(proof_to_bytes(&first), proof_to_bytes(&second));
+}
+
+#[test]
+fn test_entropy_seed_is_randomized() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, _) = setup_keys(&circuit, Some(7)).expect("Setup failed");
+    let first = prove_proof(&pk, &circuit, None).expect("Proving failed");
+    let second = prove_proof(&pk, &circuit, None).expect("Proving failed");
+    assert_ne!(proof_to_bytes(&first), proof_to_bytes(&second));
+}
+
+// Application code written against the trait, independent of the backend
+fn prove_with<P: DSAProver>(circuit: &Bls12DSACircuit) -> Result<bool, P::Error> {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let (pk, vk) = P::setup(circuit, &mut rng)?;
+    let proof = P::prove(&pk, circuit, &mut rng)?;
+    P::verify(&vk, &circuit.public_inputs(), &proof)
+}
+
+#[test]
+fn test_groth16_prover_through_trait() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    assert!(prove_with::<Groth16Prover>(&circuit).expect("Pipeline failed"));
+}
+
+#[test]
+fn test_prepared_vk_matches_unprepared() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = setup_keys(&circuit, Some(3)).expect("Setup failed");
+    let proof = prove_proof(&pk, &circuit, Some(3)).expect("Proving failed");
+    let pvk = prepare_vk(&vk);
+    let inputs = circuit.public_inputs();
+    assert!(verify_prepared(&pvk, &inputs, &proof).expect("Verification failed"));
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1] = Fr::from(1u64);
+    assert!(!verify_prepared(&pvk, &wrong_inputs, &proof).expect("Verification failed"));
+    assert!(verify_prepared(&pvk, &inputs[1..], &proof).is_err());
+}
+
+// Three signatures under the key x = 123 in the p = 2039, q = 1019 group
+fn signed_circuits() -> Vec<Bls12DSACircuit> {
+    vec![
+        Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4),
+        Bls12DSACircuit::new(572, 100, 360, 669, 2039, 1019, 4),
+        Bls12DSACircuit::new(572, 555, 344, 578, 2039, 1019, 4),
+    ]
+}
+
+#[test]
+fn test_verify_batch_accepts_valid_proofs() {
+    let circuits = signed_circuits();
+    let (pk, vk) = setup_keys(&circuits[0], Some(0)).expect("Setup failed");
+    let items: Vec<_> = circuits
+        .iter()
+        .map(|c| (c.public_inputs(), prove_proof(&pk, c, Some(1)).expect("Proving failed")))
+        .collect();
+    assert_eq!(verify_batch(&vk, &items), vec![true, true, true]);
+    assert!(verify_batch(&vk, &[]).is_empty());
+}
+
+#[test]
+fn test_verify_batch_flags_invalid_items() {
+    let circuits = signed_circuits();
+    let (pk, vk) = setup_keys(&circuits[0], Some(0)).expect("Setup failed");
+    let mut items: Vec<_> = circuits
+        .iter()
+        .map(|c| (c.public_inputs(), prove_proof(&pk, c, Some(1)).expect("Proving failed")))
+        .collect();
+    items[1].0[1] += Fr::from(1u64);
+    items[2].0.pop();
+    assert_eq!(verify_batch(&vk, &items), vec![true, false, false]);
+}
+
+#[test]
+fn test_expected_public_inputs() {
+    // y, h_x, r and s of the signature, then the domain p, q and g
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (_, vk) = setup_keys(&circuit, Some(3)).expect("Setup failed");
+    assert_eq!(expected_public_inputs(&vk), 7);
+    assert_eq!(expected_public_inputs(&vk), circuit.public_inputs().len());
+}
+
+#[test]
+fn test_verify_serialized() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = setup_keys(&circuit, Some(3)).expect("Setup failed");
+    let proof = proof_to_bytes(&prove_proof(&pk, &circuit, Some(3)).expect("Proving failed"));
+    let vk_bytes = verifying_key_to_bytes(&vk);
+    let inputs = serde_json::to_string(&DSAInputs::from(&circuit)).unwrap();
+
+    assert!(verify_serialized(&vk_bytes, &inputs, &proof).expect("Verification failed"));
+    let other_inputs = inputs.replace("\"h_x\":\"2\"", "\"h_x\":\"1\"");
+    assert!(!verify_serialized(&vk_bytes, &other_inputs, &proof).expect("Verification failed"));
+    assert!(verify_serialized(&vk_bytes, "{}", &proof).is_err());
+    assert!(verify_serialized(&vk_bytes[1..], &inputs, &proof).is_err());
+}
+
+#[test]
+fn test_prove_many_matches_sequential_proving() {
+    let circuits = signed_circuits();
+    let (pk, vk) = setup_keys(&circuits[0], Some(0)).expect("Setup failed");
+    let proofs = prove_many(&pk, circuits.clone(), 9).expect("Proving failed");
+    assert_eq!(proofs.len(), circuits.len());
+
+    for (index, (circuit, proof)) in circuits.iter().zip(&proofs).enumerate() {
+        let expected = Groth16Prover::prove(&pk, circuit, &mut derive_rng(9, index))
+            .expect("Proving failed");
+        assert_eq!(proof_to_bytes(proof), proof_to_bytes(&expected));
+        assert!(Groth16Prover::verify(&vk, &circuit.public_inputs(), proof).expect("Verification failed"));
+    }
+
+    let again = prove_many(&pk, circuits.clone(), 9).expect("Proving failed");
+    assert!(again == proofs);
+    // Identical circuits still get independent randomness
+    let repeated = prove_many(&pk, vec![circuits[0].clone(), circuits[0].clone()], 9).unwrap();
+    assert!(repeated[0] != repeated[1]);
+}
+
+#[test]
+fn test_verify_stream() {
+    let circuits = signed_circuits();
+    let (pk, vk) = setup_keys(&circuits[0], Some(0)).expect("Setup failed");
+    let mut stream = Vec::new();
+    for circuit in &circuits {
+        let proof = prove_proof(&pk, circuit, Some(1)).expect("Proving failed");
+        write_proof_frame(&mut stream, &proof).expect("Writing frame failed");
+    }
+    let mut inputs: Vec<Vec<Fr>> = circuits.iter().map(|c| c.public_inputs()).collect();
+
+    let results: Vec<bool> = verify_stream(&vk, inputs.clone(), stream.as_slice())
+        .map(|r| r.expect("Verification failed"))
+        .collect();
+    assert_eq!(results, vec![true, true, true]);
+
+    // A wrong input only fails its own proof
+    inputs[1][1] += Fr::from(1u64);
+    let results: Vec<bool> = verify_stream(&vk, inputs.clone(), stream.as_slice())
+        .map(|r| r.expect("Verification failed"))
+        .collect();
+    assert_eq!(results, vec![true, false, true]);
+
+    // Cutting into the last frame ends the stream with an I/O error
+    let truncated = &stream[..stream.len() - 10];
+    let results: Vec<_> = verify_stream(&vk, inputs.clone(), truncated).collect();
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[2], Err(PqZkpError::Io(_))));
+
+    // More inputs than proofs stops after the first missing frame
+    inputs.push(circuits[0].public_inputs());
+    inputs.push(circuits[0].public_inputs());
+    let results: Vec<_> = verify_stream(&vk, inputs, stream.as_slice()).collect();
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[3], Err(PqZkpError::Io(_))));
+}
+
+#[test]
+fn test_verify_stream_rejects_oversized_frames() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (_, vk) = setup_keys(&circuit, Some(3)).expect("Setup failed");
+    let stream = u32::MAX.to_le_bytes();
+    let results: Vec<_> = verify_stream(&vk, vec![circuit.public_inputs()], &stream[..]).collect();
+    assert!(matches!(
+        results.as_slice(),
+        [Err(PqZkpError::Io(e))] if e.kind() == std::io::ErrorKind::InvalidData
+    ));
+}
+
+#[test]
+fn test_verify_with_prepared_inputs() {
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let (pk, vk) = setup_keys(&circuit, Some(0)).expect("Setup failed");
+    let pvk = prepare_vk(&vk);
+    let inputs = circuit.public_inputs();
+    let prepared = prepare_public_inputs(&pvk, &inputs).expect("Preparing inputs failed");
+
+    // One prepared statement checks any number of proofs of it
+    for seed in 1..4 {
+        let proof = prove_proof(&pk, &circuit, Some(seed)).expect("Proving failed");
+        assert!(verify_with_prepared_inputs(&pvk, &prepared, &proof).expect("Verification failed"));
+        assert!(verify_prepared(&pvk, &inputs, &proof).unwrap());
+    }
+
+    let other = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let other_proof = prove_proof(&pk, &other, Some(1)).expect("Proving failed");
+    assert!(!verify_with_prepared_inputs(&pvk, &prepared, &other_proof).unwrap());
+
+    assert!(matches!(
+        prepare_public_inputs(&pvk, &inputs[1..]),
+        Err(PqZkpError::PublicInputCount { expected: 7, found: 6 })
+    ));
+}
+
+#[test]
+fn test_verify_or_explain() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = setup_keys(&circuit, Some(4)).expect("Setup failed");
+    let proof = proof_to_bytes(&prove_proof(&pk, &circuit, Some(4)).expect("Proving failed"));
+    let inputs = circuit.public_inputs();
+    assert!(verify_or_explain(&vk, &inputs, &proof).is_ok());
+
+    assert!(matches!(
+        verify_or_explain(&vk, &inputs[1..], &proof),
+        Err(VerificationFailure::PublicInputCount { expected: 7, found: 6 })
+    ));
+    assert!(matches!(
+        verify_or_explain(&vk, &inputs, &proof[..proof.len() - 1]),
+        Err(VerificationFailure::MalformedProof(_))
+    ));
+    let mut wrong_inputs = inputs;
+    wrong_inputs[1] = Fr::from(1u64);
+    assert!(matches!(
+        verify_or_explain(&vk, &wrong_inputs, &proof),
+        Err(VerificationFailure::PairingCheckFailed)
+    ));
+}
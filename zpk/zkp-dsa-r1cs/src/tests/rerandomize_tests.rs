@@ -0,0 +1,61 @@
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use crate::{
+    rerandomize_delta, prove_proof, setup_with_delta_updates, setup_keys, verify_delta_update,
+    Bls12DSACircuit, DSAProver, Groth16Prover, DeltaUpdate,
+};
+
+#[test]
+fn test_two_delta_updates_prove_and_verify() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let updates = [DeltaUpdate::random(&mut rng), DeltaUpdate::random(&mut rng)];
+    let (pk, vk) =
+        setup_with_delta_updates(&circuit, &updates, &mut rng).expect("Setup failed");
+    let proof = Groth16Prover::prove(&pk, &circuit, &mut rng).expect("Proving failed");
+    assert!(Groth16Prover::verify(&vk, &circuit.public_inputs(), &proof).expect("Verification failed"));
+}
+
+#[test]
+fn test_update_changes_delta() {
+    let mut rng = StdRng::seed_from_u64(1u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, _) = setup_keys(&circuit, Some(1)).expect("Setup failed");
+    let next = rerandomize_delta(&pk, &DeltaUpdate::random(&mut rng));
+    assert_ne!(next.vk.delta_g2, pk.vk.delta_g2);
+    assert!(verify_delta_update(&pk, &next, &mut rng));
+
+    // A proof under the original key no longer verifies under the new one
+    let old_proof = prove_proof(&pk, &circuit, Some(1)).expect("Proving failed");
+    let is_valid = Groth16Prover::verify(&next.vk, &circuit.public_inputs(), &old_proof)
+        .expect("Verification failed");
+    assert!(!is_valid);
+}
+
+#[test]
+fn test_verify_delta_update_rejects_malformed_updates() {
+    let mut rng = StdRng::seed_from_u64(2u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, _) = setup_keys(&circuit, Some(2)).expect("Setup failed");
+    let first = rerandomize_delta(&pk, &DeltaUpdate::random(&mut rng));
+    let second = rerandomize_delta(&pk, &DeltaUpdate::random(&mut rng));
+
+    // delta_g2 from a different update than delta_g1
+    let mut mixed = first.clone();
+    mixed.vk.delta_g2 = second.vk.delta_g2;
+    assert!(!verify_delta_update(&pk, &mixed, &mut rng));
+
+    // One H element left unscaled
+    let mut stale_h = first.clone();
+    stale_h.h_query[0] = pk.h_query[0];
+    assert!(!verify_delta_update(&pk, &stale_h, &mut rng));
+
+    // L taken from a different update
+    let mut foreign_l = first.clone();
+    foreign_l.l_query = second.l_query.clone();
+    assert!(!verify_delta_update(&pk, &foreign_l, &mut rng));
+
+    // Parts outside of delta must not change
+    let mut moved_alpha = first;
+    moved_alpha.vk.alpha_g1 = pk.delta_g1;
+    assert!(!verify_delta_update(&pk, &moved_alpha, &mut rng));
+}
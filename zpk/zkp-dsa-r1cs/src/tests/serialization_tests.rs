@@ -0,0 +1,203 @@
+use ark_bls12_381::Bls12_381;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use std::path::PathBuf;
+use crate::{
+    load_keys, proof_from_bytes, proof_to_bytes, public_inputs_from_bytes, public_inputs_to_bytes,
+    save_keys, verify_from_files, verifying_key_from_bytes, verifying_key_to_bytes,
+    BatchDSACircuit, Bls12DSACircuit, DSAParams, DSASig, PqZkpError, FORMAT_VERSION,
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("zkp-dsa-r1cs-{}-{}", name, std::process::id()))
+}
+
+#[test]
+fn test_keys_round_trip_through_disk() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+
+    let dir = scratch_dir("keys");
+    save_keys(&pk, &vk, &dir).expect("Saving keys failed");
+    let (loaded_pk, loaded_vk) = load_keys(&dir).expect("Loading keys failed");
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(loaded_vk == vk);
+    let proof = Groth16::<Bls12_381>::prove(&loaded_pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+    let is_valid = Groth16::<Bls12_381>::verify(&loaded_vk, &circuit.public_inputs(), &proof)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+// Setup draws all its randomness from the RNG it is given, so keys
+// generated in CI from a fixed seed can be regenerated byte for byte
+#[test]
+fn test_setup_is_deterministic_for_fixed_seed() {
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let setup = || {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+            .expect("Setup failed")
+    };
+    let (first_pk, first_vk) = setup();
+    let (second_pk, second_vk) = setup();
+    assert_eq!(verifying_key_to_bytes(&first_vk), verifying_key_to_bytes(&second_vk));
+    assert!(first_pk == second_pk);
+}
+
+#[test]
+fn test_load_keys_missing_dir() {
+    assert!(matches!(load_keys(&scratch_dir("missing")), Err(PqZkpError::Io(_))));
+}
+
+#[test]
+fn test_proof_round_trip_through_bytes() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+        .expect("Proving failed");
+
+    let bytes = proof_to_bytes(&proof);
+    let decoded = proof_from_bytes(&bytes).expect("Decoding failed");
+    assert!(decoded == proof);
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &circuit.public_inputs(), &decoded)
+        .expect("Verification failed");
+    assert!(is_valid);
+}
+
+#[test]
+fn test_proof_from_truncated_bytes() {
+    assert!(proof_from_bytes(&[0u8; 16]).is_err());
+}
+
+#[test]
+fn test_artifacts_with_trailing_bytes_are_rejected() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("Proving failed");
+
+    let mut proof_bytes = proof_to_bytes(&proof);
+    proof_bytes.push(0);
+    assert!(matches!(proof_from_bytes(&proof_bytes), Err(PqZkpError::Serialization(_))));
+    let mut vk_bytes = verifying_key_to_bytes(&vk);
+    vk_bytes.push(0);
+    assert!(matches!(verifying_key_from_bytes(&vk_bytes), Err(PqZkpError::Serialization(_))));
+}
+
+#[test]
+fn test_verify_from_files() {
+    let mut rng = StdRng::seed_from_u64(2u64);
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).expect("Proving failed");
+
+    let dir = scratch_dir("verify-files");
+    save_keys(&pk, &vk, &dir).expect("Saving keys failed");
+    circuit.to_json(&dir.join("inputs.json")).expect("Writing inputs failed");
+    std::fs::write(dir.join("proof.bin"), proof_to_bytes(&proof)).expect("Writing proof failed");
+    Bls12DSACircuit::new(572, 790, 758, 52, 2039, 1019, 4)
+        .to_json(&dir.join("other.json"))
+        .expect("Writing inputs failed");
+
+    // A key for a two-signature batch expects 3 + 2 * 4 public inputs
+    let params = DSAParams { p: 2039, q: 1019, g: 4 };
+    let sig = DSASig { y: 572, h_x: 789, r: 758, s: 52 };
+    let batch = BatchDSACircuit::new(params, vec![sig, sig]);
+    let (batch_pk, batch_vk) = Groth16::<Bls12_381>::circuit_specific_setup(batch, &mut rng)
+        .expect("Setup failed");
+    save_keys(&batch_pk, &batch_vk, &dir.join("batch")).expect("Saving keys failed");
+
+    let valid = verify_from_files(&dir.join("vk.bin"), &dir.join("inputs.json"), &dir.join("proof.bin"));
+    let other = verify_from_files(&dir.join("vk.bin"), &dir.join("other.json"), &dir.join("proof.bin"));
+    let mismatched =
+        verify_from_files(&dir.join("batch/vk.bin"), &dir.join("inputs.json"), &dir.join("proof.bin"));
+    let missing = verify_from_files(&dir.join("vk.bin"), &dir.join("inputs.json"), &dir.join("none.bin"));
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(valid.expect("Verification failed"));
+    assert!(!other.expect("Verification failed"));
+    assert!(matches!(mismatched, Err(PqZkpError::PublicInputCount { expected: 11, found: 7 })));
+    assert!(matches!(missing, Err(PqZkpError::Io(_))));
+}
+
+#[test]
+fn test_older_format_version_is_rejected() {
+    use ark_serialize::CanonicalSerialize;
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("Proving failed");
+    fn mismatch<T>(result: Result<T, PqZkpError>) -> bool {
+        matches!(result, Err(PqZkpError::VersionMismatch { expected: FORMAT_VERSION, found: 1 }))
+    }
+
+    // Version 1 wrote the bare canonical encoding without a header
+    let mut v1_proof = Vec::new();
+    proof.serialize(&mut v1_proof).unwrap();
+    let mut v1_vk = Vec::new();
+    vk.serialize(&mut v1_vk).unwrap();
+    assert!(mismatch(proof_from_bytes(&v1_proof)));
+    assert!(mismatch(verifying_key_from_bytes(&v1_vk)));
+
+    // A header carrying another version is refused before decoding the body
+    let mut tagged = proof_to_bytes(&proof);
+    tagged[4] = 1;
+    assert!(mismatch(proof_from_bytes(&tagged)));
+
+    let dir = scratch_dir("v1-keys");
+    std::fs::create_dir_all(&dir).expect("Creating dir failed");
+    let mut v1_pk = Vec::new();
+    pk.serialize(&mut v1_pk).unwrap();
+    std::fs::write(dir.join("pk.bin"), v1_pk).expect("Writing key failed");
+    std::fs::write(dir.join("vk.bin"), v1_vk).expect("Writing key failed");
+    let loaded = load_keys(&dir);
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(mismatch(loaded));
+}
+
+#[test]
+fn test_public_inputs_round_trip() {
+    use ark_bls12_381::Fr;
+    use ark_ff::{FpParameters, PrimeField};
+    use ark_serialize::CanonicalSerialize;
+    use num_bigint::BigUint;
+
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = Bls12DSACircuit::new(572, 789, 758, 52, 2039, 1019, 4);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).expect("Proving failed");
+
+    // The verifier needs only the bytes of the inputs, the key and the proof
+    let inputs = circuit.public_inputs();
+    let bytes = public_inputs_to_bytes(&inputs);
+    let mut canonical = Vec::new();
+    inputs.serialize(&mut canonical).unwrap();
+    assert_eq!(bytes, canonical);
+    assert_eq!(bytes.len(), 8 + 32 * inputs.len());
+    let decoded = public_inputs_from_bytes(&bytes).expect("Decoding failed");
+    assert_eq!(decoded, inputs);
+    assert!(Groth16::<Bls12_381>::verify(&vk, &decoded, &proof).unwrap());
+    assert_eq!(public_inputs_from_bytes(&public_inputs_to_bytes(&[])).unwrap(), vec![]);
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(public_inputs_from_bytes(&trailing), Err(PqZkpError::Serialization(_))));
+    assert!(public_inputs_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // The modulus itself is not a canonical element
+    let modulus: BigUint = <Fr as PrimeField>::Params::MODULUS.into();
+    let mut non_canonical = 1u64.to_le_bytes().to_vec();
+    non_canonical.extend(modulus.to_bytes_le());
+    assert!(public_inputs_from_bytes(&non_canonical).is_err());
+}
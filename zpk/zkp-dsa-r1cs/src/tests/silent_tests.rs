@@ -0,0 +1,17 @@
+// Only built with `--features silent`, where every `debug_log!` call in
+// synthesis compiles to nothing
+#![cfg(feature = "silent")]
+
+use ark_bls12_381::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use crate::{check_satisfied, Bls12DSACircuit, ElGamalVerificationCircuit};
+
+#[test]
+fn test_synthesis_with_logging_compiled_out() {
+    assert!(check_satisfied(Bls12DSACircuit::new(2, 2, 2, 1, 7, 3, 2)).unwrap());
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    ElGamalVerificationCircuit::new(503, 1000, 33, 715, 2039, 7)
+        .generate_constraints(cs.clone())
+        .expect("Synthesis failed");
+    assert!(cs.is_satisfied().unwrap());
+}
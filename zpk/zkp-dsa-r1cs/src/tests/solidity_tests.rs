@@ -0,0 +1,46 @@
+// Only built with `--features solidity`
+#![cfg(feature = "solidity")]
+
+use ark_bn254::{Bn254, Fr as BnFr};
+use ark_ff::PrimeField;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use ark_std::{rand::rngs::StdRng, rand::SeedableRng};
+use num_bigint::BigUint;
+use crate::solidity::proof_to_solidity_calldata;
+use crate::DSAVerificationCircuit;
+
+fn hex<F: PrimeField>(value: F) -> String {
+    let value: BigUint = value.into();
+    format!("0x{:064x}", value)
+}
+
+#[test]
+fn test_calldata_layout() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let circuit = DSAVerificationCircuit::<BnFr>::new(2, 2, 2, 1, 7, 3, 2);
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .expect("Setup failed");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).expect("Proving failed");
+    let inputs = circuit.public_inputs();
+    assert!(Groth16::<Bn254>::verify(&vk, &inputs, &proof).expect("Verification failed"));
+
+    let calldata = proof_to_solidity_calldata(&proof, &inputs);
+    let expected = format!(
+        "[\"{}\",\"{}\"],[[\"{}\",\"{}\"],[\"{}\",\"{}\"]],[\"{}\",\"{}\"],[{}]",
+        hex(proof.a.x),
+        hex(proof.a.y),
+        hex(proof.b.x.c1),
+        hex(proof.b.x.c0),
+        hex(proof.b.y.c1),
+        hex(proof.b.y.c0),
+        hex(proof.c.x),
+        hex(proof.c.y),
+        inputs.iter().map(|i| format!("\"{}\"", hex(*i))).collect::<Vec<_>>().join(",")
+    );
+    assert_eq!(calldata, expected);
+    assert!(calldata.ends_with(&format!(
+        "[\"0x{:064x}\",\"0x{:064x}\",\"0x{:064x}\",\"0x{:064x}\",\"0x{:064x}\",\"0x{:064x}\",\"0x{:064x}\"]",
+        2, 2, 2, 1, 7, 3, 2
+    )));
+}
@@ -0,0 +1,417 @@
+use num_bigint::BigUint;
+use ark_relations::r1cs::SynthesisError;
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use crate::utils::{
+    dsa_sign_components, dsa_verify_native, extended_gcd, find_generator, has_order, has_order_biguint,
+    hash_to_scalar, hash_to_scalar_biguint, is_probable_prime, is_probable_prime_biguint, modular_exponentiation,
+    miller_rabin_biguint, modular_exponentiation_u128, modular_exponentiation_windowed, modular_inverse,
+    MontgomeryCtx,
+};
+
+// Largest prime below 2^64
+const LARGE_PRIME: u64 = 18_446_744_073_709_551_557;
+
+fn reference_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    let result = BigUint::from(base).modpow(&BigUint::from(exp), &BigUint::from(modulus));
+    result.to_u64_digits().first().copied().unwrap_or(0)
+}
+
+#[test]
+fn test_modular_exponentiation_small() {
+    assert_eq!(modular_exponentiation(3, 1, 7), 3);
+    assert_eq!(modular_exponentiation(3, 6, 7), 1);
+    assert_eq!(modular_exponentiation(2, 10, 1_000), 24);
+}
+
+#[test]
+fn test_modular_exponentiation_large_modulus() {
+    let cases = [
+        (2, LARGE_PRIME - 1),
+        (u64::MAX - 1, 3),
+        (LARGE_PRIME - 1, 2),
+        (0x1234_5678_9abc_def0, 0xfedc_ba98_7654_3210),
+    ];
+    for (base, exp) in cases {
+        assert_eq!(
+            modular_exponentiation(base, exp, LARGE_PRIME),
+            reference_pow(base, exp, LARGE_PRIME),
+            "mismatch for base={}, exp={}",
+            base,
+            exp
+        );
+    }
+}
+
+#[test]
+fn test_modular_exponentiation_u64_max_modulus() {
+    let base = 0xdead_beef_cafe_babe;
+    let exp = 0x0123_4567_89ab_cdef;
+    assert_eq!(
+        modular_exponentiation(base, exp, u64::MAX),
+        reference_pow(base, exp, u64::MAX)
+    );
+}
+
+fn reference_pow_u128(base: u128, exp: u128, modulus: u128) -> u128 {
+    let result = BigUint::from(base).modpow(&BigUint::from(exp), &BigUint::from(modulus));
+    u128::try_from(result).expect("Result is below the modulus")
+}
+
+#[test]
+fn test_modular_exponentiation_u128_large_moduli() {
+    // 2^127 - 1 is prime, so Fermat gives base^(p - 1) = 1
+    let mersenne = (1u128 << 127) - 1;
+    assert_eq!(modular_exponentiation_u128(3, mersenne - 1, mersenne), 1);
+    let mut rng = StdRng::seed_from_u64(3u64);
+    for modulus in [mersenne, u128::MAX, u128::MAX - 158, (1 << 64) + 13] {
+        for _ in 0..50 {
+            let (base, exp): (u128, u128) = (rng.gen(), rng.gen());
+            assert_eq!(
+                modular_exponentiation_u128(base, exp, modulus),
+                reference_pow_u128(base, exp, modulus),
+                "base={}, exp={}, modulus={}",
+                base,
+                exp,
+                modulus
+            );
+        }
+    }
+    assert_eq!(modular_exponentiation_u128(5, 0, 1), 0);
+    assert_eq!(modular_exponentiation_u128(u128::MAX, 2, 7), reference_pow_u128(u128::MAX, 2, 7));
+}
+
+#[test]
+fn test_modular_exponentiation_u128_matches_u64() {
+    let mut rng = StdRng::seed_from_u64(4u64);
+    for _ in 0..200 {
+        let (base, exp): (u64, u64) = (rng.gen(), rng.gen());
+        let modulus: u64 = rng.gen_range(1..=u64::MAX);
+        assert_eq!(
+            modular_exponentiation_u128(base as u128, exp as u128, modulus as u128),
+            modular_exponentiation(base, exp, modulus) as u128
+        );
+    }
+}
+
+#[test]
+fn test_extended_gcd_bezout_identity() {
+    for (a, b) in [(2i64, 3i64), (240, 46), (0, 5), (17, 0), (1_000_000_007, 998_244_353)] {
+        let (g, x, y) = extended_gcd(a, b);
+        assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+    }
+}
+
+#[test]
+fn test_modular_inverse_small() {
+    assert_eq!(modular_inverse(2, 3).unwrap(), 2);
+    assert_eq!(modular_inverse(3, 7).unwrap(), 5);
+    assert!(modular_inverse(3, 6).is_err());
+}
+
+#[test]
+fn test_modular_inverse_near_u64_max() {
+    for (a, m) in [
+        (u64::MAX - 1, LARGE_PRIME),
+        (2, u64::MAX),
+        (LARGE_PRIME - 1, LARGE_PRIME),
+        (0x1234_5678_9abc_def1, u64::MAX),
+    ] {
+        let inv = modular_inverse(a, m).expect("Inverse should exist");
+        let product = (a as u128 * inv as u128) % m as u128;
+        assert_eq!(product, 1, "a={}, m={}", a, m);
+    }
+}
+
+#[test]
+fn test_windowed_matches_square_and_multiply() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    for _ in 0..1_000 {
+        let base: u64 = rng.gen();
+        let exp: u64 = rng.gen();
+        let modulus: u64 = rng.gen_range(1..=u64::MAX);
+        assert_eq!(
+            modular_exponentiation_windowed(base, exp, modulus),
+            modular_exponentiation(base, exp, modulus),
+            "base={}, exp={}, modulus={}",
+            base,
+            exp,
+            modulus
+        );
+    }
+    for exp in 0..64 {
+        assert_eq!(
+            modular_exponentiation_windowed(3, exp, LARGE_PRIME),
+            modular_exponentiation(3, exp, LARGE_PRIME)
+        );
+        assert_eq!(modular_exponentiation_windowed(5, exp, 1), modular_exponentiation(5, exp, 1));
+    }
+}
+
+#[test]
+fn test_montgomery_mul_round_trip() {
+    let mut rng = StdRng::seed_from_u64(1u64);
+    for modulus in [3u64, 7, 1019, LARGE_PRIME, u64::MAX] {
+        let ctx = MontgomeryCtx::new(modulus);
+        for _ in 0..200 {
+            let a = rng.gen::<u64>() % modulus;
+            let b = rng.gen::<u64>() % modulus;
+            assert_eq!(ctx.from_montgomery(ctx.to_montgomery(a)), a);
+            let product = ctx.from_montgomery(ctx.mul(ctx.to_montgomery(a), ctx.to_montgomery(b)));
+            assert_eq!(product as u128, a as u128 * b as u128 % modulus as u128);
+        }
+    }
+}
+
+#[test]
+fn test_montgomery_pow_matches_reference() {
+    let mut rng = StdRng::seed_from_u64(2u64);
+    for _ in 0..500 {
+        let modulus = rng.gen::<u64>() | 1;
+        let base: u64 = rng.gen();
+        let exp: u64 = rng.gen();
+        let ctx = MontgomeryCtx::new(modulus);
+        assert_eq!(ctx.pow(base, exp), reference_pow(base, exp, modulus));
+    }
+}
+
+#[test]
+fn test_modular_inverse_zero_modulus() {
+    assert!(matches!(modular_inverse(5, 0), Err(SynthesisError::DivisionByZero)));
+    assert!(matches!(modular_inverse(1, 0), Err(SynthesisError::DivisionByZero)));
+    assert!(matches!(modular_inverse(0, 0), Err(SynthesisError::DivisionByZero)));
+}
+
+#[test]
+fn test_modular_inverse_of_zero() {
+    assert!(matches!(modular_inverse(0, 7), Err(SynthesisError::AssignmentMissing)));
+    assert!(matches!(modular_inverse(0, LARGE_PRIME), Err(SynthesisError::AssignmentMissing)));
+}
+
+#[test]
+fn test_extended_gcd_both_zero() {
+    assert_eq!(extended_gcd(0, 0), (0, 1, 0));
+}
+
+mod properties {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+    use crate::utils::{extended_gcd, modular_exponentiation, modular_inverse};
+
+    fn gcd(mut a: u64, mut b: u64) -> u64 {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    proptest! {
+        #[test]
+        fn modular_inverse_is_an_inverse(a in any::<u64>(), m in 2u64..) {
+            prop_assume!(gcd(a, m) == 1);
+            let inv = modular_inverse(a, m).unwrap();
+            prop_assert!(inv < m);
+            let product = BigUint::from(a) * BigUint::from(inv) % BigUint::from(m);
+            prop_assert_eq!(product, BigUint::from(1u64));
+        }
+
+        #[test]
+        fn modular_inverse_rejects_common_factors(a in any::<u64>(), m in 2u64..) {
+            prop_assume!(gcd(a, m) != 1);
+            prop_assert!(modular_inverse(a, m).is_err());
+        }
+
+        #[test]
+        fn extended_gcd_satisfies_bezout(a in any::<i64>(), b in any::<i64>()) {
+            let (g, x, y) = extended_gcd(a, b);
+            prop_assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+
+        #[test]
+        fn modular_exponentiation_matches_biguint(
+            base in any::<u64>(),
+            exp in any::<u64>(),
+            modulus in 1u64..,
+        ) {
+            let expected = BigUint::from(base).modpow(&BigUint::from(exp), &BigUint::from(modulus));
+            prop_assert_eq!(BigUint::from(modular_exponentiation(base, exp, modulus)), expected);
+        }
+    }
+}
+
+// SHA-256("abc") from FIPS 180-2, appendix B.1
+const SHA256_ABC: [u8; 32] = [
+    0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+    0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+];
+
+#[test]
+fn test_hash_to_scalar_truncates_to_leftmost_bits() {
+    // q = 2^61 - 1: the leftmost 61 bits are the first 8 bytes shifted by 3
+    let q = (1u64 << 61) - 1;
+    assert_eq!(hash_to_scalar(&SHA256_ABC, q), (0xba7816bf8f01cfea >> 3) % q);
+    // q = 1019 has 10 bits: 0xba78 >> 6 = 745
+    assert_eq!(hash_to_scalar(&SHA256_ABC, 1019), 745);
+    // Short digests are used whole
+    assert_eq!(hash_to_scalar(&[0x01, 0x02], q), 0x0102);
+}
+
+#[test]
+fn test_hash_to_scalar_matches_biguint_variant() {
+    for q in [3u64, 1019, (1 << 61) - 1, u64::MAX] {
+        assert_eq!(
+            BigUint::from(hash_to_scalar(&SHA256_ABC, q)),
+            hash_to_scalar_biguint(&SHA256_ABC, &BigUint::from(q)),
+            "q = {}",
+            q
+        );
+    }
+    // A 256-bit q takes the full digest
+    let q = (BigUint::from(1u32) << 256) - 189u32;
+    assert_eq!(hash_to_scalar_biguint(&SHA256_ABC, &q), BigUint::from_bytes_be(&SHA256_ABC) % &q);
+}
+
+#[test]
+fn test_has_order_built_in_example() {
+    // 3 is a primitive root mod 7, so its order is 6, not q = 3
+    assert!(!has_order(3, 3, 7));
+    // 2^3 = 8 = 1 mod 7
+    assert!(has_order(2, 3, 7));
+    assert!(has_order(4, 3, 7));
+    // The identity satisfies g^q = 1 but generates nothing
+    assert!(!has_order(1, 3, 7));
+    assert!(!has_order(8, 3, 7));
+    assert!(!has_order(0, 3, 7));
+}
+
+#[test]
+fn test_has_order_fips_style_groups() {
+    // q | p - 1 and g = h^((p - 1) / q) mod p, as in FIPS 186-4 appendix A.2.1
+    for (p, q, h) in [(23u64, 11u64, 2u64), (283, 47, 2), (1019, 509, 2), (2_147_483_647, 331, 7)] {
+        assert_eq!((p - 1) % q, 0);
+        let g = modular_exponentiation(h, (p - 1) / q, p);
+        assert!(has_order(g, q, p), "p={}, q={}, g={}", p, q, g);
+        assert!(!has_order(g, q - 1, p), "p={}, q={}, g={}", p, q, g);
+        assert!(has_order_biguint(&BigUint::from(g), &BigUint::from(q), &BigUint::from(p)));
+    }
+    assert!(!has_order(2, 3, 1));
+    assert!(!has_order_biguint(&BigUint::from(2u32), &BigUint::from(3u32), &BigUint::from(1u32)));
+}
+
+#[test]
+fn test_dsa_verify_native() {
+    // Signature under x = 123 in the p = 2039, q = 1019 group
+    assert!(dsa_verify_native(572, 789, 758, 52, 2039, 1019, 4));
+    assert!(!dsa_verify_native(572, 789, 758, 53, 2039, 1019, 4));
+    assert!(!dsa_verify_native(572, 790, 758, 52, 2039, 1019, 4));
+    // Out-of-range r and s are rejected before any arithmetic
+    assert!(!dsa_verify_native(572, 789, 758 + 1019, 52, 2039, 1019, 4));
+    assert!(!dsa_verify_native(572, 789, 758, 0, 2039, 1019, 4));
+    assert!(!dsa_verify_native(572, 789, 0, 52, 2039, 1019, 4));
+}
+
+#[test]
+fn test_is_probable_prime_table() {
+    let primes = [2u64, 3, 37, 41, 1019, 2039, 65_537, 2_147_483_647, LARGE_PRIME];
+    // Carmichael numbers, a square, a product of two large primes and
+    // strong pseudoprimes to the smallest bases
+    let composites = [0u64, 1, 4, 561, 1105, 6601, 1_019 * 1_019, 2_047, 3_215_031_751, 4_294_967_297];
+    for n in primes {
+        assert!(is_probable_prime(n, 12), "{} is prime", n);
+        assert!(is_probable_prime(n, 1), "{} is prime", n);
+    }
+    for n in composites {
+        assert!(!is_probable_prime(n, 12), "{} is composite", n);
+    }
+}
+
+#[test]
+fn test_is_probable_prime_rounds() {
+    // 3215031751 is a strong pseudoprime to bases 2, 3, 5 and 7 but not 11
+    assert!(is_probable_prime(3_215_031_751, 4));
+    assert!(!is_probable_prime(3_215_031_751, 5));
+    // 2047 = 23 * 89 fools base 2 alone, but trial division catches it
+    assert!(!is_probable_prime(2_047, 1));
+    assert!(is_probable_prime(LARGE_PRIME, 100));
+}
+
+#[test]
+fn test_is_probable_prime_biguint() {
+    let m61 = (BigUint::from(1u32) << 61u32) - 1u32;
+    let m89 = (BigUint::from(1u32) << 89u32) - 1u32;
+    let m127 = (BigUint::from(1u32) << 127u32) - 1u32;
+    assert!(is_probable_prime_biguint(&m89, 12));
+    assert!(is_probable_prime_biguint(&m127, 12));
+    assert!(!is_probable_prime_biguint(&(&m61 * &m89), 12));
+    assert!(!is_probable_prime_biguint(&(&m127 * 1_000_003u32), 12));
+    assert!(is_probable_prime_biguint(&BigUint::from(LARGE_PRIME), 12));
+    assert!(!is_probable_prime_biguint(&BigUint::from(561u32), 12));
+}
+
+#[test]
+fn test_is_probable_prime_agrees_with_biguint_path() {
+    // Every odd n above the bases, with the strong pseudoprimes 2047 and
+    // 3215031751 in range of the fewer-round checks
+    let odd = (41u64..20_000).step_by(2).chain([3_215_031_751, LARGE_PRIME]);
+    for n in odd {
+        for rounds in [1, 4, 12] {
+            assert_eq!(
+                is_probable_prime(n, rounds),
+                miller_rabin_biguint(&BigUint::from(n), rounds),
+                "n = {}, rounds = {}",
+                n,
+                rounds
+            );
+        }
+    }
+}
+
+#[test]
+fn test_find_generator_has_order_q() {
+    for (p, q) in [(7u64, 3u64), (23, 11), (2039, 1019), (2039, 2), (65_537, 2), (1_000_003, 166_667)] {
+        let g = find_generator(p, q).expect("q divides p - 1");
+        assert!(g > 1 && g < p);
+        assert!(has_order(g, q, p), "{} must have order {} mod {}", g, q, p);
+    }
+    // The only element of order 2 is -1
+    assert_eq!(find_generator(LARGE_PRIME, 2), Some(LARGE_PRIME - 1));
+}
+
+#[test]
+fn test_find_generator_rejects_invalid_domains() {
+    assert_eq!(find_generator(2039, 1018), None, "q must be prime");
+    assert_eq!(find_generator(2039, 1021), None, "q must divide p - 1");
+    assert_eq!(find_generator(2041, 1020), None, "p must be prime");
+    assert_eq!(find_generator(0, 0), None);
+    assert_eq!(find_generator(1, 3), None);
+}
+
+#[test]
+fn test_dsa_sign_components_verify() {
+    // x = 123 and k = 456 give the (758, 52) signature on h = 789 used
+    // throughout the tests
+    let (p, q, g, x) = (2039u64, 1019u64, 4u64, 123u64);
+    let y = modular_exponentiation(g, x, p);
+    assert_eq!(dsa_sign_components(456, x, 789, p, q, g), Some((758, 52)));
+
+    let mut rng = StdRng::seed_from_u64(5u64);
+    for _ in 0..200 {
+        let (k, h) = (rng.gen_range(1..q), rng.gen_range(0..q));
+        if let Some((r, s)) = dsa_sign_components(k, x, h, p, q, g) {
+            assert!(dsa_verify_native(y, h, r, s, p, q, g), "k = {}, h = {}", k, h);
+            assert!(!dsa_verify_native(y, (h + 1) % q, r, s, p, q, g));
+        }
+    }
+}
+
+#[test]
+fn test_dsa_sign_components_rejects_degenerate_nonces() {
+    let (p, q, g) = (2039u64, 1019u64, 4u64);
+    // k = q has no inverse mod q and also makes r = 1
+    assert_eq!(dsa_sign_components(q, 123, 789, p, q, g), None);
+    assert_eq!(dsa_sign_components(0, 123, 789, p, q, g), None);
+    // h = -x * r mod q forces s = 0
+    let (r, _) = dsa_sign_components(456, 123, 789, p, q, g).unwrap();
+    let h = (q - 123 * r % q) % q;
+    assert_eq!(dsa_sign_components(456, 123, h, p, q, g), None);
+    assert_eq!(dsa_sign_components(456, 123, 789, 1, q, g), None);
+}
@@ -1,25 +1,248 @@
 use ark_relations::r1cs::SynthesisError;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use subtle::ConstantTimeEq;
 
+// Fails with `DivisionByZero` for m == 0 and `AssignmentMissing` when a has
+// no inverse modulo m
 pub fn modular_inverse(a: u64, m: u64) -> Result<u64, SynthesisError> {
-    let (g, x, _) = extended_gcd(a as i64, m as i64);
+    if m == 0 {
+        return Err(SynthesisError::DivisionByZero);
+    }
+    let (g, x, _) = extended_gcd_wide(a as i128, m as i128);
     if g != 1 {
         return Err(SynthesisError::AssignmentMissing);
     }
-    Ok(((x % m as i64 + m as i64) % m as i64) as u64)
+    let m = m as i128;
+    Ok(((x % m + m) % m) as u64)
+}
+
+pub fn modular_inverse_biguint(a: &BigUint, m: &BigUint) -> Result<BigUint, SynthesisError> {
+    if m.is_zero() {
+        return Err(SynthesisError::DivisionByZero);
+    }
+    a.modinv(m).ok_or(SynthesisError::AssignmentMissing)
 }
 
+// Returns (g, x, y) with a*x + b*y = g. For a == b == 0 there is no gcd and
+// the result is (0, 1, 0), so callers must not reduce or divide by g
+// without checking it is non-zero first.
 pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
-    if a == 0 {
-        (b, 0, 1)
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+// Iterative extended Euclid; i128 keeps u64-sized operands and their
+// Bezout coefficients from overflowing
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    (old_r, old_x, old_y)
+}
+
+// DSA's reduction of a message digest (FIPS 186-4, section 4.6): if the
+// digest has more bits than q, keep only its leftmost bitlen(q) bits, read
+// big-endian; otherwise use the whole digest. The result is reduced mod q,
+// so q must be non-zero; q = 0 panics.
+pub fn hash_to_scalar(digest: &[u8], q: u64) -> u64 {
+    let q_bits = 64 - q.leading_zeros() as usize;
+    let digest_bits = digest.len() * 8;
+    let z = if digest_bits <= q_bits {
+        digest.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
     } else {
-        let (g, x, y) = extended_gcd(b % a, a);
-        (g, y - (b / a) * x, x)
+        // The leftmost q_bits bits all lie within the first ceil(q_bits / 8) bytes
+        let num_bytes = q_bits.div_ceil(8);
+        let top = digest[..num_bytes].iter().fold(0u128, |acc, &b| (acc << 8) | b as u128);
+        (top >> (num_bytes * 8 - q_bits)) as u64
+    };
+    z % q
+}
+
+// `hash_to_scalar` for moduli wider than a u64. Panics, like it, if q is 0.
+pub fn hash_to_scalar_biguint(digest: &[u8], q: &BigUint) -> BigUint {
+    let digest_bits = (digest.len() * 8) as u64;
+    let mut z = BigUint::from_bytes_be(digest);
+    if q.bits() < digest_bits {
+        z >>= digest_bits - q.bits();
+    }
+    z % q
+}
+
+// `hash_to_scalar` for a message of field elements hashed with Poseidon
+// instead of SHA-256: the low bitlen(q) bits of `poseidon::poseidon_digest`,
+// reduced mod q. This is the h_x `PoseidonDSACircuit` derives in the circuit.
+// Panics, like `hash_to_scalar`, if q is 0.
+#[cfg(feature = "poseidon")]
+pub fn poseidon_hash_to_scalar(msg: &[ark_bls12_381::Fr], q: u64) -> ark_bls12_381::Fr {
+    crate::circuit::to_field(&crate::poseidon::poseidon_scalar(msg, &BigUint::from(q)))
+}
+
+const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// Deterministic Miller-Rabin; the first twelve primes as bases are enough
+// for every n < 2^64
+pub fn is_prime(n: u64) -> bool {
+    is_probable_prime(n, MILLER_RABIN_BASES.len())
+}
+
+// Miller-Rabin with the first `rounds` primes as witness bases, capped at
+// twelve. Primes always pass; with all twelve rounds no composite u64 does,
+// and fewer rounds trade certainty for speed.
+pub fn is_probable_prime(n: u64, rounds: usize) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &MILLER_RABIN_BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    // n - 1 = d * 2^shift; a^d is squared at most shift - 1 times, as the
+    // shift-th square is a^(n - 1), which no longer tells primes apart
+    let shift = (n - 1).trailing_zeros();
+    let d = (n - 1) >> shift;
+    let rounds = rounds.min(MILLER_RABIN_BASES.len());
+    'witness: for &a in &MILLER_RABIN_BASES[..rounds] {
+        let mut x = modular_exponentiation(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..shift {
+            x = ((x as u128 * x as u128) % n as u128) as u64;
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
     }
+    true
 }
 
+// `is_probable_prime` for values wider than a u64. The same fixed bases
+// give no proven bound here, but a composite passing all twelve is not a
+// practical concern for parameter validation.
+pub fn is_probable_prime_biguint(n: &BigUint, rounds: usize) -> bool {
+    if let Ok(n) = u64::try_from(n) {
+        return is_probable_prime(n, rounds);
+    }
+    miller_rabin_biguint(n, rounds)
+}
+
+// The wide path of `is_probable_prime_biguint` for any n above the twelve
+// bases, u64 or not; the tests cross-check it against `is_probable_prime`
+pub(crate) fn miller_rabin_biguint(n: &BigUint, rounds: usize) -> bool {
+    for &p in &MILLER_RABIN_BASES {
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+    let one = BigUint::from(1u32);
+    let n_minus_1 = n - &one;
+    let shift = n_minus_1.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_1 >> shift;
+    let rounds = rounds.min(MILLER_RABIN_BASES.len());
+    'witness: for &a in &MILLER_RABIN_BASES[..rounds] {
+        let mut x = BigUint::from(a).modpow(&d, n);
+        if x == one || x == n_minus_1 {
+            continue;
+        }
+        for _ in 1..shift {
+            x = &x * &x % n;
+            if x == n_minus_1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// True when g is a non-trivial q-th root of unity mod p: g^q = 1 and g != 1.
+// For prime q this is exactly "g generates the order-q subgroup", which the
+// DSA circuit assumes of its generator.
+pub fn has_order(g: u64, q: u64, p: u64) -> bool {
+    p > 1 && g % p != 1 && modular_exponentiation(g, q, p) == 1
+}
+
+// Smallest g = h^((p-1)/q) mod p over h = 2, 3, ... that is not 1, which
+// then has order exactly q. `None` unless p and q are prime and q | p - 1.
+// Fewer than one h in q collapses to 1, so the search ends almost at once.
+pub fn find_generator(p: u64, q: u64) -> Option<u64> {
+    if !is_prime(p) || !is_prime(q) || !(p - 1).is_multiple_of(q) {
+        return None;
+    }
+    (2..p)
+        .map(|h| modular_exponentiation(h, (p - 1) / q, p))
+        .find(|&g| g != 1)
+}
+
+// `has_order` for parameters wider than a u64
+pub fn has_order_biguint(g: &BigUint, q: &BigUint, p: &BigUint) -> bool {
+    let one = BigUint::from(1u32);
+    *p > one && g % p != one && g.modpow(q, p) == one
+}
+
+// The signer's half of `dsa_verify_native`: with nonce k and private key x,
+// r = (g^k mod p) mod q and s = k^-1 * (h + x * r) mod q. `None` when k has
+// no inverse mod q or either component comes out zero, in which case DSA
+// signs again with a fresh k.
+pub fn dsa_sign_components(k: u64, x: u64, h: u64, p: u64, q: u64, g: u64) -> Option<(u64, u64)> {
+    if p < 2 || q < 2 {
+        return None;
+    }
+    let r = modular_exponentiation(g, k, p) % q;
+    let k_inv = modular_inverse(k % q, q).ok()?;
+    let q_wide = q as u128;
+    let xr = x as u128 * r as u128 % q_wide;
+    let s = (k_inv as u128 * ((h as u128 + xr) % q_wide) % q_wide) as u64;
+    if r == 0 || s == 0 {
+        return None;
+    }
+    Some((r, s))
+}
+
+// Textbook DSA verification over u64 parameters, the reference the circuit
+// is tested against: with 0 < r, s < q, compute w = s^-1 mod q,
+// u1 = h_x * w mod q, u2 = r * w mod q and accept iff
+// (g^u1 * y^u2 mod p) mod q == r.
+//
+// Only that final comparison runs in constant time, so how far v and r
+// agree doesn't leak through timing. The range checks return early and the
+// inverse and exponentiations are not constant time in their operands.
+pub fn dsa_verify_native(y: u64, h_x: u64, r: u64, s: u64, p: u64, q: u64, g: u64) -> bool {
+    if r == 0 || r >= q || s == 0 || s >= q || p < 2 {
+        return false;
+    }
+    let w = match modular_inverse(s, q) {
+        Ok(w) => w as u128,
+        Err(_) => return false,
+    };
+    let (p_wide, q_wide) = (p as u128, q as u128);
+    let u1 = (h_x as u128 * w % q_wide) as u64;
+    let u2 = (r as u128 * w % q_wide) as u64;
+    let g_u1 = modular_exponentiation(g, u1, p) as u128;
+    let y_u2 = modular_exponentiation(y, u2, p) as u128;
+    let v = (g_u1 * y_u2 % p_wide) as u64 % q;
+    v.ct_eq(&r).into()
+}
+
+// Intermediate products are widened to u128 so any u64 modulus is safe.
+// Odd moduli go through Montgomery multiplication to avoid the 128-bit `%`.
 pub fn modular_exponentiation(base: u64, exp: u64, modulus: u64) -> u64 {
-    let mut result = 1u64;
-    let mut base = base % modulus;
+    if modulus > 1 && modulus & 1 == 1 {
+        return MontgomeryCtx::new(modulus).pow(base, exp);
+    }
+    let modulus = modulus as u128;
+    // Reduced so that modulus 1 yields 0 even when exp == 0
+    let mut result = 1u128 % modulus;
+    let mut base = base as u128 % modulus;
     let mut exp = exp;
     while exp > 0 {
         if exp & 1 == 1 {
@@ -28,5 +251,128 @@ pub fn modular_exponentiation(base: u64, exp: u64, modulus: u64) -> u64 {
         base = (base * base) % modulus;
         exp >>= 1;
     }
+    result as u64
+}
+
+// Montgomery arithmetic modulo an odd u64 with R = 2^64. Values passed to
+// `mul` must already be in Montgomery form (see `to_montgomery`).
+#[derive(Clone, Copy, Debug)]
+pub struct MontgomeryCtx {
+    modulus: u64,
+    // -modulus^-1 mod 2^64
+    neg_inv: u64,
+}
+
+impl MontgomeryCtx {
+    pub fn new(modulus: u64) -> Self {
+        assert!(modulus & 1 == 1, "Montgomery reduction needs an odd modulus");
+        // Newton iteration: each step doubles the number of correct low bits
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.wrapping_mul(inv)));
+        }
+        MontgomeryCtx { modulus, neg_inv: inv.wrapping_neg() }
+    }
+
+    // Computes t * R^-1 mod n for t < n * R
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.neg_inv);
+        let (sum, carry) = t.overflowing_add(m as u128 * self.modulus as u128);
+        let reduced = (sum >> 64) | ((carry as u128) << 64);
+        if reduced >= self.modulus as u128 {
+            (reduced - self.modulus as u128) as u64
+        } else {
+            reduced as u64
+        }
+    }
+
+    pub fn to_montgomery(&self, a: u64) -> u64 {
+        (((a as u128) << 64) % self.modulus as u128) as u64
+    }
+
+    pub fn from_montgomery(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    // base^exp mod n, taking and returning ordinary (non-Montgomery) values
+    pub fn pow(&self, base: u64, exp: u64) -> u64 {
+        let mut result = self.to_montgomery(1);
+        let mut base = self.to_montgomery(base);
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        self.from_montgomery(result)
+    }
+}
+
+// 4-bit fixed-window variant of `modular_exponentiation`: precomputes
+// base^0..base^15 and then consumes the exponent a nibble at a time, trading
+// the per-bit multiply for one table lookup per four squarings
+pub fn modular_exponentiation_windowed(base: u64, exp: u64, modulus: u64) -> u64 {
+    if exp == 0 {
+        return (1 % modulus as u128) as u64;
+    }
+    let modulus = modulus as u128;
+    let mut table = [1u128; 16];
+    table[1] = base as u128 % modulus;
+    for i in 2..16 {
+        table[i] = (table[i - 1] * table[1]) % modulus;
+    }
+
+    let top_nibble = (63 - exp.leading_zeros()) / 4;
+    let mut result = table[(exp >> (top_nibble * 4)) as usize & 0xf];
+    for nibble in (0..top_nibble).rev() {
+        for _ in 0..4 {
+            result = (result * result) % modulus;
+        }
+        result = (result * table[(exp >> (nibble * 4)) as usize & 0xf]) % modulus;
+    }
+    result as u64
+}
+
+// (a + b) mod m for a, b < m, without overflowing when a + b > u128::MAX
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// a * b mod m for a, b < m. The full product needs 256 bits, so this
+// doubles and adds over the bits of b instead of widening.
+fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    let mut result = 0;
+    for bit in (0..128 - b.leading_zeros()).rev() {
+        result = add_mod_u128(result, result, m);
+        if (b >> bit) & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+    }
+    result
+}
+
+// `modular_exponentiation` for u128 moduli. Each multiplication costs up to
+// 128 modular additions, so prefer the u64 version when the modulus fits.
+pub fn modular_exponentiation_u128(base: u128, exp: u128, modulus: u128) -> u128 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, modulus);
+        }
+        base = mul_mod_u128(base, base, modulus);
+        exp >>= 1;
+    }
     result
 }
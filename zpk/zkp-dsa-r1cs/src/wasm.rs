@@ -0,0 +1,14 @@
+use wasm_bindgen::prelude::*;
+use crate::prover::verify_serialized;
+
+// Browser entry point around `verify_serialized`. Returns whether the proof
+// is valid; a malformed key, proof or input JSON throws instead, so callers
+// can tell bad data apart from a failed verification.
+#[wasm_bindgen]
+pub fn verify_proof(
+    vk_bytes: &[u8],
+    public_inputs_json: &str,
+    proof_bytes: &[u8],
+) -> Result<bool, JsError> {
+    verify_serialized(vk_bytes, public_inputs_json, proof_bytes).map_err(|e| JsError::new(&e.to_string()))
+}
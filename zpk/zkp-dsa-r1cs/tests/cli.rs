@@ -0,0 +1,112 @@
+// Runs the compiled binary the way a user would and checks its output and
+// exit codes: 0 for a valid proof, 1 for an invalid one, 2 for errors
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn run(args: &[&str], env: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zkp-dsa-r1cs"))
+        .args(args)
+        .env("PQ_ZKP_SEED", "0")
+        .envs(env.iter().copied())
+        .output()
+        .expect("Binary runs")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// A fresh directory per test, as tests run in parallel
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pq-zkp-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Creating scratch directory failed");
+    dir
+}
+
+#[test]
+fn test_default_example_verifies() {
+    let output = run(&[], &[]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout(&output).contains("Proof verification result: true"));
+}
+
+#[test]
+fn test_json_report() {
+    let output = run(&["--json"], &[]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("Output is JSON");
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["public_inputs"].as_array().map(Vec::len), Some(7));
+}
+
+#[test]
+fn test_wrong_signature_from_env_fails() {
+    let output = run(&[], &[("PQ_ZKP_R", "1")]);
+    assert!(!output.status.success());
+    assert!(!stdout(&output).contains("Proof verification result: true"));
+}
+
+#[test]
+fn test_invalid_parameters_exit_with_error() {
+    // s = 2 has no inverse mod q = 6
+    let output = run(&[], &[("PQ_ZKP_Q", "6")]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error:"));
+}
+
+#[test]
+fn test_generated_params_prove_and_verify() {
+    let dir = scratch_dir("workflow");
+    let path = |name: &str| dir.join(name).to_str().expect("UTF-8 path").to_owned();
+    let (params, keys, proof) = (path("params.json"), path("keys"), path("proof.bin"));
+
+    assert!(run(&["params", "gen", "--bits", "16", "--out", &params], &[]).status.success());
+    assert!(run(&["setup", "--params", &params, "--out-dir", &keys], &[]).status.success());
+    let pk = format!("{}/pk.bin", keys);
+    let prove = run(&["prove", "--pk", &pk, "--params", &params, "--out", &proof], &[]);
+    assert!(prove.status.success());
+
+    let vk = format!("{}/vk.bin", keys);
+    let verify =
+        |params: &str| run(&["verify", "--vk", &vk, "--params", params, "--proof", &proof], &[]);
+    let output = verify(&params);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("Proof verification result: true"));
+
+    // The proof does not carry over to a different parameter file
+    let other = path("other.json");
+    let gen = run(&["--seed", "1", "params", "gen", "--bits", "16", "--out", &other], &[]);
+    assert!(gen.status.success());
+    let output = verify(&other);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Proof verification result: false"));
+
+    let args = ["verify", "--vk", &vk, "--params", &other, "--proof", &proof];
+    let output = run(&[&args[..], &["--prover-params", &params]].concat(), &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("public inputs differ"));
+    assert!(stderr.contains("p: "));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_params_gen_at_minimum_width() {
+    let dir = scratch_dir("min-width");
+    let params = dir.join("params.json");
+    let params = params.to_str().expect("UTF-8 path");
+    for seed in ["0", "1", "2", "3", "4", "5", "6", "7"] {
+        let gen = run(&["--seed", seed, "params", "gen", "--bits", "6", "--out", params], &[]);
+        assert_eq!(gen.status.code(), Some(0), "seed {}", seed);
+        let keys = dir.join("keys");
+        let setup = run(&["setup", "--params", params, "--out-dir", keys.to_str().unwrap()], &[]);
+        assert!(setup.status.success(), "seed {}", seed);
+    }
+    // Below that q < 5 and no nonce signs, so the width is rejected upfront
+    let gen = run(&["params", "gen", "--bits", "5", "--out", params], &[]);
+    assert_eq!(gen.status.code(), Some(2));
+    fs::remove_dir_all(&dir).ok();
+}